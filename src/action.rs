@@ -1,5 +1,5 @@
 use crate::event::{KeyEvent, RelativeEvent};
-use evdev::InputEvent;
+use evdev::{AbsoluteAxisCode, InputEvent};
 use std::time::Duration;
 
 // Input to ActionDispatcher. This should only contain things that are easily testable.
@@ -7,10 +7,14 @@ use std::time::Duration;
 pub enum Action {
     // InputEvent (EventType::KEY) sent to evdev
     KeyEvent(KeyEvent),
+    // InputEvent (EventType::KEY) sent to evdev with its MSC_SCAN, in the same frame
+    ScannedKeyEvent(KeyEvent, i32),
     // InputEvent (EventType::RELATIVE, NOT mouse movement events) sent to evdev
     RelativeEvent(RelativeEvent),
     // InputEvent (EventType::RELATIVE, ONLY mouse movement events) sent to evdev
     MouseMovementEventCollection(Vec<RelativeEvent>),
+    // InputEvent (EventType::ABSOLUTE) sent to the gamepad device
+    GamepadAxis(AbsoluteAxisCode, i32),
     // InputEvent of any event types. It's discouraged to use this for testing because
     // we don't have full control over timeval and it's not pattern-matching friendly.
     InputEvent(InputEvent),
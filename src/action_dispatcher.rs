@@ -1,34 +1,104 @@
 use crate::client::print_windows;
+use crate::device::{is_button, is_gamepad_button, Capabilities};
 use crate::event::RelativeEvent;
+use crate::event_handler::{PRESS, RELEASE};
 use crate::main_controller::MainController;
 use crate::main_impl::MainAction;
 use crate::throttle_emit::ThrottleEmit;
 use crate::{action::Action, event::KeyEvent};
 use anyhow::Context;
-use evdev::{uinput::VirtualDevice, EventType, InputEvent, KeyCode as Key};
+use evdev::{uinput::VirtualDevice, AbsoluteAxisCode, EventType, InputEvent, KeyCode as Key, MiscCode};
 use log::{debug, error};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
 use std::thread;
 
 pub struct ActionDispatcher {
-    // Device to emit events
-    device: VirtualDevice,
+    // Devices to emit events, shared with the panic hook
+    outputs: Arc<Mutex<Outputs>>,
     // Throttle emitting events
     throttle_emit: Option<ThrottleEmit>,
+    // The input device of the events being dispatched. None for timeouts.
+    source: Option<PathBuf>,
+}
+
+// The output devices, and the keys pressed on them
+pub struct Outputs {
+    // Device to emit events
+    device: VirtualDevice,
+    // Device to emit mouse buttons and relative events, with split_output
+    mouse: Option<VirtualDevice>,
+    // Device to emit gamepad buttons and axes, with enable_gamepad
+    gamepad: Option<VirtualDevice>,
+    // Devices to emit the events of each input device, with output_per_device
+    per_device: HashMap<PathBuf, (VirtualDevice, Capabilities)>,
+    // Keys pressed on the output devices. They're released on the same output,
+    // and when xremap stops or the input device is removed.
+    pressed_keys: PressedKeys,
 }
 
 impl ActionDispatcher {
-    pub fn new(device: VirtualDevice, throttle_emit: Option<ThrottleEmit>) -> ActionDispatcher {
-        ActionDispatcher { device, throttle_emit }
+    pub fn new(
+        device: VirtualDevice,
+        mouse: Option<VirtualDevice>,
+        gamepad: Option<VirtualDevice>,
+        throttle_emit: Option<ThrottleEmit>,
+    ) -> ActionDispatcher {
+        let outputs = Outputs {
+            device,
+            mouse,
+            gamepad,
+            per_device: HashMap::new(),
+            pressed_keys: PressedKeys::default(),
+        };
+        ActionDispatcher {
+            outputs: Arc::new(Mutex::new(outputs)),
+            throttle_emit,
+            source: None,
+        }
+    }
+
+    // For the panic hook. It's weak, so the devices are still dropped with the dispatcher.
+    pub fn outputs(&self) -> Weak<Mutex<Outputs>> {
+        Arc::downgrade(&self.outputs)
+    }
+
+    pub fn set_source(&mut self, source: Option<PathBuf>) {
+        self.source = source;
+    }
+
+    pub fn has_output(&self, path: &Path) -> bool {
+        self.lock().per_device.contains_key(path)
+    }
+
+    pub fn add_output(&mut self, path: PathBuf, device: VirtualDevice, capabilities: Capabilities) {
+        self.lock().per_device.insert(path, (device, capabilities));
+    }
+
+    pub fn retain_outputs(&mut self, keep: impl Fn(&Path) -> bool) {
+        self.lock().per_device.retain(|path, _| keep(path));
+    }
+
+    pub fn release_pressed_keys(&mut self, device: Option<&Path>) {
+        self.lock().release_pressed_keys(device);
+    }
+
+    // A panic while emitting poisons the lock, but the devices are still usable.
+    fn lock(&self) -> MutexGuard<'_, Outputs> {
+        self.outputs.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
-    // Execute Actions created by EventHandler. This should be the only public method of ActionDispatcher.
+    // Execute Actions created by EventHandler. The other public methods only manage the output devices.
     pub fn on_action(&mut self, action: Action, mainctrl: &mut MainController) -> anyhow::Result<Option<MainAction>> {
         match action {
-            Action::KeyEvent(key_event) => self.on_key_event(key_event)?,
+            Action::KeyEvent(key_event) => self.on_key_event(key_event, None)?,
+            Action::ScannedKeyEvent(key_event, scan) => self.on_key_event(key_event, Some(scan))?,
             Action::RelativeEvent(relative_event) => self.on_relative_event(relative_event)?,
             Action::MouseMovementEventCollection(mouse_movement_events) => {
                 self.send_mousemovement_event_batch(mouse_movement_events)?;
             }
+            Action::GamepadAxis(axis, value) => self.on_gamepad_axis(axis, value)?,
             Action::InputEvent(event) => self.send_event(event)?,
             Action::Command(command) => mainctrl.run_command(command),
             Action::Delay(duration) => thread::sleep(duration),
@@ -59,8 +129,10 @@ impl ActionDispatcher {
     ) -> anyhow::Result<Option<MainAction>> {
         match action {
             Action::KeyEvent(_)
+            | Action::ScannedKeyEvent(_, _)
             | Action::RelativeEvent(_)
             | Action::MouseMovementEventCollection(_)
+            | Action::GamepadAxis(_, _)
             | Action::InputEvent(_)
             | Action::Command(_)
             | Action::Delay(_)
@@ -96,9 +168,26 @@ impl ActionDispatcher {
         Ok(format!("title: {title}\napp: {app_class}"))
     }
 
-    fn on_key_event(&mut self, event: KeyEvent) -> std::io::Result<()> {
+    fn on_key_event(&mut self, event: KeyEvent, scan: Option<i32>) -> std::io::Result<()> {
         let event = InputEvent::new_now(EventType::KEY.0, event.code(), event.value());
-        self.send_event(event)
+        let scan = scan.map(|scan| InputEvent::new_now(EventType::MISC.0, MiscCode::MSC_SCAN.0, scan));
+        let source = self.source.clone();
+        let mut outputs = self.lock();
+        if outputs.gamepad.is_some() && is_gamepad_button(event.code()) {
+            debug!("{}: {:?} (gamepad)", event.value(), Key::new(event.code()));
+            return outputs.emit_key(event, scan, source);
+        }
+        drop(outputs);
+        self.send_key(event, scan)
+    }
+
+    fn on_gamepad_axis(&mut self, axis: AbsoluteAxisCode, value: i32) -> std::io::Result<()> {
+        let mut outputs = self.lock();
+        let Some(gamepad) = &mut outputs.gamepad else {
+            debug!("Ignoring {axis:?} without enable_gamepad");
+            return Ok(());
+        };
+        gamepad.emit(&[InputEvent::new_now(EventType::ABSOLUTE.0, axis.0, value)])
     }
 
     fn on_relative_event(&mut self, event: RelativeEvent) -> std::io::Result<()> {
@@ -119,7 +208,11 @@ impl ActionDispatcher {
                 mouse_movement.value,
             ));
         }
-        self.device.emit(&mousemovementbatch)
+        let source = self.source.clone();
+        match mousemovementbatch.first() {
+            Some(event) => self.lock().output_for(event, source).emit(&mousemovementbatch),
+            None => Ok(()),
+        }
     }
 
     fn send_event(&mut self, event: InputEvent) -> std::io::Result<()> {
@@ -130,14 +223,134 @@ impl ActionDispatcher {
         }
 
         if event.event_type() == EventType::KEY {
-            // Throttle
-            if let Some(throttle_emit) = &mut self.throttle_emit {
-                throttle_emit.sleep_if_needed(Key(event.code()), event.value());
-            };
+            return self.send_key(event, None);
+        }
+
+        let source = self.source.clone();
+        self.lock().output_for(&event, source).emit(&[event])
+    }
+
+    // The scan code is emitted in the same frame as its key, on the same output.
+    fn send_key(&mut self, event: InputEvent, scan: Option<InputEvent>) -> std::io::Result<()> {
+        // Throttle
+        if let Some(throttle_emit) = &mut self.throttle_emit {
+            throttle_emit.sleep_if_needed(Key(event.code()), event.value());
+        };
+
+        debug!("{}: {:?}", event.value(), Key::new(event.code()));
 
-            debug!("{}: {:?}", event.value(), Key::new(event.code()))
+        let source = self.source.clone();
+        self.lock().emit_key(event, scan, source)
+    }
+}
+
+impl Outputs {
+    // Release the keys pressed for an input device, or all pressed keys, so that nothing stays stuck.
+    pub fn release_pressed_keys(&mut self, device: Option<&Path>) {
+        for (source, code) in self.pressed_keys.pressed_for(device) {
+            if let Err(error) = self.emit_key(InputEvent::new_now(EventType::KEY.0, code, RELEASE), None, source) {
+                error!("Failed to release {:?}: {error}", Key::new(code));
+            }
+        }
+    }
+
+    fn emit_key(
+        &mut self,
+        event: InputEvent,
+        scan: Option<InputEvent>,
+        source: Option<PathBuf>,
+    ) -> std::io::Result<()> {
+        let source = self.pressed_keys.track(&event, source);
+        let events: Vec<InputEvent> = scan.into_iter().chain([event]).collect();
+        match &mut self.gamepad {
+            Some(gamepad) if is_gamepad_button(event.code()) => gamepad.emit(&events),
+            _ => self.output_for(&event, source).emit(&events),
+        }
+    }
+
+    fn output_for(&mut self, event: &InputEvent, source: Option<PathBuf>) -> &mut VirtualDevice {
+        let output = source.and_then(|source| self.per_device.get_mut(&source));
+        match route(event, output.as_ref().map(|(_, capabilities)| capabilities), self.mouse.is_some()) {
+            Output::Source => &mut output.expect("only routed to an existing output").0,
+            Output::Mouse => self.mouse.as_mut().expect("only routed to an existing mouse"),
+            Output::Main => &mut self.device,
+        }
+    }
+}
+
+// Keys pressed on the output devices, with the input device they were pressed for.
+// The same key can be pressed for several input devices, each on its own output.
+#[derive(Default)]
+pub struct PressedKeys {
+    keys: HashSet<(Option<PathBuf>, u16)>,
+}
+
+impl PressedKeys {
+    // Updates the pressed keys, and returns the input device whose output the event goes to
+    pub fn track(&mut self, event: &InputEvent, source: Option<PathBuf>) -> Option<PathBuf> {
+        let key = (source, event.code());
+        if event.value() == PRESS {
+            let source = key.0.clone();
+            self.keys.insert(key);
+            return source;
+        }
+
+        // A key released by a timeout or another device goes to the output it was pressed on
+        let pressed = if self.keys.contains(&key) {
+            Some(key.clone())
+        } else {
+            self.keys.iter().find(|(_, code)| *code == key.1).cloned()
+        };
+        match pressed {
+            Some(pressed) if event.value() == RELEASE => {
+                self.keys.remove(&pressed);
+                pressed.0
+            }
+            Some(pressed) => pressed.0,
+            None => key.0,
         }
+    }
+
+    // The keys pressed for an input device, or all pressed keys
+    pub fn pressed_for(&self, device: Option<&Path>) -> Vec<(Option<PathBuf>, u16)> {
+        self.keys
+            .iter()
+            .filter(|(source, _)| device.is_none() || source.as_deref() == device)
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Output {
+    Source,
+    Mouse,
+    Main,
+}
+
+// The output of the source device with output_per_device, if it supports the event, else the mouse device
+// with split_output or the output device. The same event always goes to the same output, so releases do too.
+pub fn route(event: &InputEvent, source: Option<&Capabilities>, split_mouse: bool) -> Output {
+    if source.is_some_and(|capabilities| capabilities.supports(event)) {
+        Output::Source
+    } else if split_mouse && is_mouse_event(event) {
+        Output::Mouse
+    } else {
+        Output::Main
+    }
+}
+
+impl Drop for ActionDispatcher {
+    // Covers exiting, reloading, errors, and panics that unwind. The panic hook covers the other panics.
+    fn drop(&mut self) {
+        self.release_pressed_keys(None);
+    }
+}
 
-        self.device.emit(&[event])
+fn is_mouse_event(event: &InputEvent) -> bool {
+    match event.event_type() {
+        EventType::RELATIVE => true,
+        EventType::KEY => is_button(event.code()),
+        _ => false,
     }
 }
@@ -0,0 +1,74 @@
+use super::application::OnlyOrNot;
+use super::deserialize_keys;
+use evdev::KeyCode as Key;
+use serde::Deserialize;
+use serde_with::{serde_as, DurationMilliSeconds};
+use std::time::Duration;
+
+// Holding a letter or digit beyond the timeout emits it shifted.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AutoShift {
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(default = "default_timeout", alias = "timeout_millis")]
+    pub timeout: Duration,
+    // Keys that are never shifted.
+    #[serde(default, deserialize_with = "deserialize_keys")]
+    pub exclude: Vec<Key>,
+    pub application: Option<OnlyOrNot>,
+    pub window: Option<OnlyOrNot>,
+}
+
+impl AutoShift {
+    pub fn keys(&self) -> Vec<Key> {
+        AUTO_SHIFT_KEYS
+            .iter()
+            .filter(|key| !self.exclude.contains(key))
+            .copied()
+            .collect()
+    }
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_millis(180)
+}
+
+static AUTO_SHIFT_KEYS: [Key; 36] = [
+    Key::KEY_A,
+    Key::KEY_B,
+    Key::KEY_C,
+    Key::KEY_D,
+    Key::KEY_E,
+    Key::KEY_F,
+    Key::KEY_G,
+    Key::KEY_H,
+    Key::KEY_I,
+    Key::KEY_J,
+    Key::KEY_K,
+    Key::KEY_L,
+    Key::KEY_M,
+    Key::KEY_N,
+    Key::KEY_O,
+    Key::KEY_P,
+    Key::KEY_Q,
+    Key::KEY_R,
+    Key::KEY_S,
+    Key::KEY_T,
+    Key::KEY_U,
+    Key::KEY_V,
+    Key::KEY_W,
+    Key::KEY_X,
+    Key::KEY_Y,
+    Key::KEY_Z,
+    Key::KEY_1,
+    Key::KEY_2,
+    Key::KEY_3,
+    Key::KEY_4,
+    Key::KEY_5,
+    Key::KEY_6,
+    Key::KEY_7,
+    Key::KEY_8,
+    Key::KEY_9,
+    Key::KEY_0,
+];
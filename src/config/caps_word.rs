@@ -0,0 +1,53 @@
+use super::deserialize_keys;
+use evdev::KeyCode as Key;
+use serde::Deserialize;
+use serde_with::{serde_as, DurationMilliSeconds};
+use std::time::Duration;
+
+// Options for the `{ caps_word: true }` action
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapsWord {
+    // Keys that don't end the word, in addition to letters.
+    #[serde(default = "default_continue_keys", deserialize_with = "deserialize_keys")]
+    pub continue_keys: Vec<Key>,
+    // Emit `-` as `_`
+    #[serde(default)]
+    pub underscore: bool,
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(default = "default_timeout", alias = "timeout_millis")]
+    pub timeout: Duration,
+}
+
+impl Default for CapsWord {
+    fn default() -> Self {
+        CapsWord {
+            continue_keys: default_continue_keys(),
+            underscore: false,
+            timeout: default_timeout(),
+        }
+    }
+}
+
+fn default_continue_keys() -> Vec<Key> {
+    vec![
+        Key::KEY_1,
+        Key::KEY_2,
+        Key::KEY_3,
+        Key::KEY_4,
+        Key::KEY_5,
+        Key::KEY_6,
+        Key::KEY_7,
+        Key::KEY_8,
+        Key::KEY_9,
+        Key::KEY_0,
+        Key::KEY_MINUS,
+        Key::KEY_BACKSPACE,
+        Key::KEY_DELETE,
+    ]
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_millis(5000)
+}
@@ -0,0 +1,26 @@
+use super::deserialize_keys;
+use super::device::DeviceMatcher;
+use evdev::KeyCode as Key;
+use serde::Deserialize;
+use serde_with::{serde_as, DurationMilliSeconds};
+use std::time::Duration;
+
+// Drop key chatter from the matching devices.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Debounce {
+    pub device: Option<DeviceMatcher>,
+    // The keys to debounce. Empty means all keys.
+    #[serde(default, deserialize_with = "deserialize_keys")]
+    pub keys: Vec<Key>,
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(alias = "window_millis")]
+    pub window: Duration,
+}
+
+impl Debounce {
+    pub fn applies_to(&self, key: Key) -> bool {
+        self.keys.is_empty() || self.keys.contains(&key)
+    }
+}
@@ -0,0 +1,35 @@
+use super::deserialize_keys;
+use evdev::KeyCode as Key;
+use serde::Deserialize;
+use serde_with::{serde_as, DurationMilliSeconds};
+use std::time::Duration;
+
+// Keys that ungrab all devices and exit xremap. It can be changed, but not turned off.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmergencyEscape {
+    #[serde(default = "default_keys", deserialize_with = "deserialize_keys")]
+    pub keys: Vec<Key>,
+    // How long the keys are held together
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(default = "default_hold", rename = "hold_millis")]
+    pub hold: Duration,
+}
+
+impl Default for EmergencyEscape {
+    fn default() -> Self {
+        EmergencyEscape {
+            keys: default_keys(),
+            hold: default_hold(),
+        }
+    }
+}
+
+fn default_keys() -> Vec<Key> {
+    vec![Key::KEY_LEFTSHIFT, Key::KEY_RIGHTSHIFT, Key::KEY_ESC]
+}
+
+fn default_hold() -> Duration {
+    Duration::from_secs(3)
+}
@@ -1,4 +1,6 @@
-use crate::config::application::OnlyOrNot;
+use super::device::DeviceMatcher;
+use crate::config::application::{deserialize_string_or_vec, OnlyOrNot};
+use crate::config::expmap_sequence::Sequence;
 use crate::config::modmap::KeyWrapper;
 use crate::config::{expmap_operator::ExpmapOperator, expmap_simkey::Simkey};
 use evdev::KeyCode as Key;
@@ -13,10 +15,15 @@ pub struct Expmap {
     pub name: String,
     #[serde(default)]
     pub chords: Vec<Simkey>,
+    #[serde(default)]
+    pub sequences: Vec<Sequence>,
     #[serde(default, deserialize_with = "deserialize_experimental_remap")]
     pub remap: IndexMap<Key, ExpmapOperator>,
     pub application: Option<OnlyOrNot>,
     pub window: Option<OnlyOrNot>,
+    pub device: Option<DeviceMatcher>,
+    #[serde(default, deserialize_with = "deserialize_string_or_vec")]
+    pub mode: Option<Vec<String>>,
 }
 
 pub fn deserialize_experimental_remap<'de, D>(deserializer: D) -> Result<IndexMap<Key, ExpmapOperator>, D::Error>
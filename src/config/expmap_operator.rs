@@ -1,4 +1,5 @@
 use crate::config::key::deserialize_key;
+use crate::config::keymap_action::KeymapAction;
 use evdev::KeyCode as Key;
 use serde::{Deserialize, Deserializer};
 use serde_with::{serde_as, DurationMilliSeconds};
@@ -9,6 +10,7 @@ use std::time::Duration;
 #[serde(untagged)]
 pub enum ExpmapOperator {
     DoubleTap(DoubleTap),
+    TapDance(TapDance),
 }
 
 #[serde_as]
@@ -22,11 +24,30 @@ pub struct DoubleTap {
     pub timeout: Duration,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+pub struct TapDance {
+    // Actions for one, two, three, ... taps.
+    #[serde(deserialize_with = "deserialize_expmap_actions_list")]
+    pub taps: Vec<Vec<ExpmapAction>>,
+
+    // Actions when the key is held on the first, second, third, ... press.
+    #[serde(default, deserialize_with = "deserialize_expmap_actions_list")]
+    pub hold: Vec<Vec<ExpmapAction>>,
+
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(default = "default_tapdance_timeout")]
+    pub timeout: Duration,
+}
+
+#[derive(Clone, Debug, Deserialize)]
 #[serde(untagged)]
 pub enum ExpmapAction {
+    // Pressed, repeated and released along with the trigger.
     #[serde(deserialize_with = "deserialize_key")]
     Key(Key),
+    // Dispatched like in keymap, when the trigger is pressed.
+    KeymapAction(KeymapAction),
 }
 
 // Used only for deserializing
@@ -55,6 +76,20 @@ where
     Ok(ExpmapActions::deserialize(deserializer)?.into_vec())
 }
 
+pub fn deserialize_expmap_actions_list<'de, D>(deserializer: D) -> Result<Vec<Vec<ExpmapAction>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Vec::<ExpmapActions<ExpmapAction>>::deserialize(deserializer)?
+        .into_iter()
+        .map(ExpmapActions::into_vec)
+        .collect())
+}
+
 fn default_dbltap_timeout() -> Duration {
     Duration::from_millis(200)
 }
+
+fn default_tapdance_timeout() -> Duration {
+    Duration::from_millis(200)
+}
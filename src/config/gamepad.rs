@@ -0,0 +1,64 @@
+use crate::device::GAMEPAD_AXES;
+use evdev::AbsoluteAxisCode;
+use indexmap::IndexMap;
+use serde::{Deserialize, Deserializer};
+use std::str::FromStr;
+
+// Options for the pseudo-keys of gamepad axes like `XABS_X_PLUS`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Gamepad {
+    // How far an axis is moved from rest to press the pseudo-key. 1 is all the way.
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+    // The pseudo-key is released, when the axis is back within this distance from rest.
+    #[serde(default = "default_release_threshold")]
+    pub release_threshold: f64,
+}
+
+impl Default for Gamepad {
+    fn default() -> Self {
+        Gamepad {
+            threshold: default_threshold(),
+            release_threshold: default_release_threshold(),
+        }
+    }
+}
+
+fn default_threshold() -> f64 {
+    0.5
+}
+
+fn default_release_threshold() -> f64 {
+    0.3
+}
+
+// Positions of the `{ axis: { ABS_X: -32767 } }` action
+#[derive(Clone, Debug)]
+pub struct Axes(pub Vec<(AbsoluteAxisCode, i32)>);
+
+impl<'de> Deserialize<'de> for Axes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let axes = IndexMap::<String, i32>::deserialize(deserializer)?;
+        let axes = axes
+            .into_iter()
+            .map(|(name, value)| Ok((parse_axis(&name).map_err(serde::de::Error::custom)?, value)))
+            .collect::<Result<Vec<_>, D::Error>>()?;
+        Ok(Axes(axes))
+    }
+}
+
+// Axes of the gamepad device, with or without "ABS_".
+fn parse_axis(input: &str) -> Result<AbsoluteAxisCode, String> {
+    let name = input.to_uppercase();
+    let name = if name.starts_with("ABS_") {
+        name
+    } else {
+        format!("ABS_{name}")
+    };
+
+    AbsoluteAxisCode::from_str(&name)
+        .ok()
+        .filter(|axis| GAMEPAD_AXES.iter().any(|(gamepad_axis, _, _)| gamepad_axis == axis))
+        .ok_or_else(|| format!("Unknown gamepad axis '{input}'"))
+}
@@ -0,0 +1,34 @@
+use serde::Deserialize;
+
+// Options for recognizing touchpad gestures like `XLEFTSWIPE3` and `XPINCHIN`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Gestures {
+    // Distance the fingers move for a swipe, in touchpad units.
+    #[serde(default = "default_swipe_distance")]
+    pub swipe_distance: i32,
+    // Change of the distance between two fingers for a pinch, in touchpad units.
+    #[serde(default = "default_pinch_distance")]
+    pub pinch_distance: i32,
+    // Touches of three or more fingers aren't passed on, so the desktop doesn't handle swipes too.
+    #[serde(default)]
+    pub consume_swipes: bool,
+}
+
+impl Default for Gestures {
+    fn default() -> Self {
+        Gestures {
+            swipe_distance: default_swipe_distance(),
+            pinch_distance: default_pinch_distance(),
+            consume_swipes: false,
+        }
+    }
+}
+
+fn default_swipe_distance() -> i32 {
+    300
+}
+
+fn default_pinch_distance() -> i32 {
+    300
+}
@@ -0,0 +1,30 @@
+use super::deserialize_keys;
+use evdev::KeyCode as Key;
+use serde::Deserialize;
+
+// Which hand types each key, for bilateral multi-purpose keys.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hands {
+    #[serde(default, deserialize_with = "deserialize_keys")]
+    pub left: Vec<Key>,
+    #[serde(default, deserialize_with = "deserialize_keys")]
+    pub right: Vec<Key>,
+}
+
+impl Hands {
+    // The keys on the other hand than the given key. Empty if the key isn't on a hand.
+    pub fn opposite_hand(&self, key: Key) -> Vec<Key> {
+        if self.left.contains(&key) {
+            self.right.clone()
+        } else if self.right.contains(&key) {
+            self.left.clone()
+        } else {
+            vec![]
+        }
+    }
+
+    pub fn contains(&self, key: Key) -> bool {
+        self.left.contains(&key) || self.right.contains(&key)
+    }
+}
@@ -0,0 +1,26 @@
+use super::device::DeviceMatcher;
+use serde::Deserialize;
+use serde_with::{serde_as, DurationMilliSeconds};
+use std::time::Duration;
+
+// A press only counts if the key is held for the delay.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SlowKeys {
+    pub device: Option<DeviceMatcher>,
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(alias = "delay_millis")]
+    pub delay: Duration,
+}
+
+// A press is ignored if the same key was released within the delay.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BounceKeys {
+    pub device: Option<DeviceMatcher>,
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(alias = "delay_millis")]
+    pub delay: Duration,
+}
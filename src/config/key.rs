@@ -1,7 +1,8 @@
 use crate::config::key_press::parse_modifier_alias;
 use crate::event_handler::{DISGUISED_EVENT_OFFSETTER, KEY_MATCH_ANY};
+use crate::gamepad::absolute_key;
 use anyhow::Context;
-use evdev::KeyCode as Key;
+use evdev::{AbsoluteAxisCode, KeyCode as Key};
 use serde::{Deserialize, Deserializer};
 use std::error::Error;
 use std::str::FromStr;
@@ -34,6 +35,9 @@ where
 //    REL_WHEEL_HI_RES  | XHIRES_UPSCROLL     | XHIRES_DOWNSCROLL
 //    REL_HWHEEL_HI_RES | XHIRES_RIGHTSCROLL  | XHIRES_LEFTSCROLL
 //
+// Axes of gamepads from ABS_X to ABS_HAT3Y are pseudo keys too, e.g. ABS_HAT0X is
+// XABS_HAT0X_PLUS when moved right and XABS_HAT0X_MINUS when moved left.
+//
 pub fn parse_key(input: &str) -> Result<Key, Box<dyn Error>> {
     // Everything is case-insensitive
     let name = input.to_uppercase();
@@ -55,6 +59,11 @@ pub fn parse_key(input: &str) -> Result<Key, Box<dyn Error>> {
         return Ok(Key(key_code));
     }
 
+    // Axes of gamepads, (e.g. XABS_X_PLUS)
+    if let Some(key) = parse_absolute_key(&name) {
+        return Ok(key);
+    }
+
     // xremap's custom aliases like k0kubun/karabiner-dsl
     match &name[..] {
         // Shift
@@ -127,6 +136,18 @@ pub fn parse_key(input: &str) -> Result<Key, Box<dyn Error>> {
         "XHIRES_RIGHTSCROLL" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 24)),
         "XHIRES_LEFTSCROLL" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 25)),
 
+        // Touchpad gestures
+        "XRIGHTSWIPE3" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 32)),
+        "XLEFTSWIPE3" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 33)),
+        "XDOWNSWIPE3" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 34)),
+        "XUPSWIPE3" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 35)),
+        "XRIGHTSWIPE4" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 36)),
+        "XLEFTSWIPE4" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 37)),
+        "XDOWNSWIPE4" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 38)),
+        "XUPSWIPE4" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 39)),
+        "XPINCHIN" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 40)),
+        "XPINCHOUT" => Ok(Key(DISGUISED_EVENT_OFFSETTER + 41)),
+
         // Any key
         "ANY" => Ok(KEY_MATCH_ANY),
 
@@ -141,6 +162,16 @@ pub fn parse_key(input: &str) -> Result<Key, Box<dyn Error>> {
     }
 }
 
+fn parse_absolute_key(name: &str) -> Option<Key> {
+    let axis = name.strip_prefix('X')?;
+    let (axis, positive) = match axis.strip_suffix("_PLUS") {
+        Some(axis) => (axis, true),
+        None => (axis.strip_suffix("_MINUS")?, false),
+    };
+    let axis = AbsoluteAxisCode::from_str(axis).ok()?;
+    absolute_key(axis, positive).map(Key)
+}
+
 #[test]
 fn test_parse_key() {
     // Can omit the 'KEY_' prefex
@@ -170,3 +201,11 @@ fn test_parse_literal_key_code() {
     assert_eq!(parse_key("Code_abc").unwrap_err().to_string(), "Invalid key_code in: CODE_ABC");
     assert_eq!(parse_key("Code_70000").unwrap_err().to_string(), "Invalid key_code in: CODE_70000");
 }
+
+#[test]
+fn test_parse_absolute_key() {
+    assert_eq!(parse_key("XABS_X_PLUS").unwrap(), Key(DISGUISED_EVENT_OFFSETTER + 48));
+    assert_eq!(parse_key("Xabs_hat0y_minus").unwrap(), Key(DISGUISED_EVENT_OFFSETTER + 48 + 0x11 * 2 + 1));
+
+    assert_eq!(parse_key("XABS_MT_SLOT_PLUS").unwrap_err().to_string(), "Unknown key 'XABS_MT_SLOT_PLUS'");
+}
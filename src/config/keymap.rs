@@ -4,6 +4,7 @@ use crate::config::application::deserialize_string_or_vec;
 use crate::config::application::OnlyOrNot;
 use crate::config::key_press::KeyPress;
 use crate::config::keymap_action::{Actions, KeymapAction};
+use crate::config::led::LockMatcher;
 use evdev::KeyCode as Key;
 use indexmap::IndexMap;
 use serde::{Deserialize, Deserializer};
@@ -23,6 +24,7 @@ pub struct Keymap {
     pub device: Option<DeviceMatcher>,
     #[serde(default, deserialize_with = "deserialize_string_or_vec")]
     pub mode: Option<Vec<String>>,
+    pub lock: Option<LockMatcher>,
     #[serde(default)]
     pub exact_match: bool,
 }
@@ -48,6 +50,7 @@ pub struct KeymapEntry {
     pub title: Option<OnlyOrNot>,
     pub device: Option<DeviceMatcher>,
     pub mode: Option<Vec<String>>,
+    pub lock: Option<LockMatcher>,
     pub exact_match: bool,
 }
 
@@ -68,6 +71,7 @@ pub fn build_keymap_table(keymaps: &Vec<Keymap>) -> HashMap<Key, Vec<KeymapEntry
                 title: keymap.window.clone(),
                 device: keymap.device.clone(),
                 mode: keymap.mode.clone(),
+                lock: keymap.lock.clone(),
                 exact_match: keymap.exact_match,
             };
             match table.get_mut(&key_press.key) {
@@ -1,7 +1,9 @@
 use crate::config::deserialize_single_field;
+use crate::config::gamepad::Axes;
 use crate::config::key::parse_key;
 use crate::config::key_press::KeyPress;
 use crate::config::keymap_action_without_args::ActionWithoutArgs;
+use crate::config::mouse_keys::{MouseMove, Scroll};
 use crate::config::nested_remap::{deserialize_nested_remap, Remap};
 use evdev::KeyCode as Key;
 use serde::{de, Deserialize, Deserializer};
@@ -32,6 +34,16 @@ pub enum KeymapAction {
     WithMark(KeyPress),
     #[serde(deserialize_with = "deserialize_escape_next_key")]
     EscapeNextKey(bool),
+    #[serde(deserialize_with = "deserialize_caps_word")]
+    CapsWord(bool),
+    #[serde(deserialize_with = "deserialize_mouse_move")]
+    MouseMove(MouseMove),
+    #[serde(deserialize_with = "deserialize_scroll")]
+    Scroll(Scroll),
+    #[serde(deserialize_with = "deserialize_drag_scroll")]
+    DragScroll(bool),
+    #[serde(deserialize_with = "deserialize_axis")]
+    Axis(Axes),
     #[serde(deserialize_with = "deserialize_sleep")]
     Sleep(u64),
     #[serde(deserialize_with = "deserialize_close_by_app_class")]
@@ -147,6 +159,19 @@ where
     Err(de::Error::custom("not a map with a single \"escape_next_key\" key"))
 }
 
+fn deserialize_caps_word<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut action = HashMap::<String, bool>::deserialize(deserializer)?;
+    if let Some(set) = action.remove("caps_word") {
+        if action.is_empty() {
+            return Ok(set);
+        }
+    }
+    Err(de::Error::custom("not a map with a single \"caps_word\" key"))
+}
+
 fn deserialize_sleep<'de, D>(deserializer: D) -> Result<u64, D::Error>
 where
     D: Deserializer<'de>,
@@ -160,6 +185,22 @@ where
     Err(de::Error::custom("not a map with a single \"sleep\" key"))
 }
 
+fn deserialize_mouse_move<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MouseMove, D::Error> {
+    deserialize_single_field(deserializer, "mouse_move")
+}
+
+fn deserialize_scroll<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Scroll, D::Error> {
+    deserialize_single_field(deserializer, "scroll")
+}
+
+fn deserialize_drag_scroll<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    deserialize_single_field(deserializer, "drag_scroll")
+}
+
+fn deserialize_axis<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Axes, D::Error> {
+    deserialize_single_field(deserializer, "axis")
+}
+
 pub fn deserialize_close_by_app_class<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
     deserialize_single_field(deserializer, "close_apps")
 }
@@ -0,0 +1,70 @@
+use crate::config::application::deserialize_string_or_vec;
+use crate::config::deserializers::VecOrSingle;
+use evdev::LedCode;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+// Lights an LED of the grabbed keyboards from the state of xremap
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LedIndicator {
+    #[serde(deserialize_with = "deserialize_led")]
+    pub led: LedCode,
+    // Lit while in one of these modes
+    #[serde(default, deserialize_with = "deserialize_string_or_vec")]
+    pub mode: Option<Vec<String>>,
+    // Lit while the mark is set
+    #[serde(default)]
+    pub mark: bool,
+}
+
+impl LedIndicator {
+    pub fn is_lit(&self, mode: &str, mark_set: bool) -> bool {
+        self.mode.as_ref().is_some_and(|modes| modes.iter().any(|m| m == mode)) || (self.mark && mark_set)
+    }
+}
+
+// `lock: { only: [numlock] }` of keymap and modmap
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LockMatcher {
+    #[serde(default, deserialize_with = "deserialize_leds")]
+    pub only: Option<Vec<LedCode>>,
+    #[serde(default, deserialize_with = "deserialize_leds")]
+    pub not: Option<Vec<LedCode>>,
+}
+
+impl LockMatcher {
+    // All locks in `only` are on, and all locks in `not` are off.
+    pub fn matches(&self, locks: &HashSet<LedCode>) -> bool {
+        self.only.iter().flatten().all(|led| locks.contains(led))
+            && !self.not.iter().flatten().any(|led| locks.contains(led))
+    }
+}
+
+fn deserialize_leds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<LedCode>>, D::Error> {
+    let leds = VecOrSingle::<String>::deserialize(deserializer)?
+        .into_vec()
+        .iter()
+        .map(|led| parse_led(led))
+        .collect::<Result<_, _>>()
+        .map_err(serde::de::Error::custom)?;
+    Ok(Some(leds))
+}
+
+fn deserialize_led<'de, D: Deserializer<'de>>(deserializer: D) -> Result<LedCode, D::Error> {
+    parse_led(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+// Names like `capslock` or `LED_CAPSL`
+pub fn parse_led(input: &str) -> Result<LedCode, String> {
+    match input.to_lowercase().as_str() {
+        "numlock" => Ok(LedCode::LED_NUML),
+        "capslock" => Ok(LedCode::LED_CAPSL),
+        "scrolllock" => Ok(LedCode::LED_SCROLLL),
+        "compose" => Ok(LedCode::LED_COMPOSE),
+        "kana" => Ok(LedCode::LED_KANA),
+        _ => LedCode::from_str(&input.to_uppercase()).map_err(|_| format!("unknown LED: {input}")),
+    }
+}
@@ -1,24 +1,49 @@
 pub mod application;
+pub mod auto_shift;
+pub mod caps_word;
+pub mod debounce;
 pub mod deserializers;
 pub mod device;
+pub mod emergency_escape;
 pub mod expmap;
 pub mod expmap_operator;
+pub mod expmap_sequence;
 pub mod expmap_simkey;
+pub mod gamepad;
+pub mod gestures;
+pub mod hands;
+pub mod input_filter;
 pub mod key;
 pub mod key_press;
 pub mod keymap;
 pub mod keymap_action;
 pub mod keymap_action_without_args;
+pub mod led;
 pub mod modmap;
 pub mod modmap_operator;
+pub mod mouse_keys;
 pub mod nested_remap;
+pub mod pointer;
+pub mod split_output;
 #[cfg(test)]
 mod tests;
 pub mod validation;
 
+use crate::config::auto_shift::AutoShift;
+use crate::config::caps_word::CapsWord;
+use crate::config::debounce::Debounce;
+use crate::config::emergency_escape::EmergencyEscape;
 use crate::config::expmap::Expmap;
+use crate::config::gamepad::Gamepad;
+use crate::config::gestures::Gestures;
+use crate::config::hands::Hands;
+use crate::config::input_filter::{BounceKeys, SlowKeys};
 use crate::config::key::parse_key;
 use crate::config::keymap::{build_keymap_table, Keymap, KeymapEntry};
+use crate::config::led::LedIndicator;
+use crate::config::mouse_keys::MouseKeys;
+use crate::config::pointer::Pointer;
+use crate::config::split_output::SplitOutput;
 use crate::config::validation::validate_config_file;
 use crate::event_handler::DISGUISED_EVENT_OFFSETTER;
 use crate::event_handler::MODIFIER_KEYS;
@@ -44,9 +69,34 @@ pub struct Config {
     #[serde(deserialize_with = "deserialize_virtual_modifier", default = "Vec::new")]
     pub virtual_modifiers: Vec<Key>,
     #[serde(default)]
+    pub hands: Hands,
+    #[serde(default)]
+    pub caps_word: CapsWord,
+    pub auto_shift: Option<AutoShift>,
+    #[serde(default)]
+    pub mouse_keys: MouseKeys,
+    #[serde(default = "Vec::new")]
+    pub pointer: Vec<Pointer>,
+    #[serde(default)]
+    pub gestures: Gestures,
+    #[serde(default)]
+    pub gamepad: Gamepad,
+    #[serde(default = "Vec::new")]
+    pub led_indicators: Vec<LedIndicator>,
+    #[serde(default)]
+    pub emergency_escape: EmergencyEscape,
+    #[serde(default = "Vec::new")]
+    pub slow_keys: Vec<SlowKeys>,
+    #[serde(default = "Vec::new")]
+    pub bounce_keys: Vec<BounceKeys>,
+    #[serde(default = "Vec::new")]
+    pub debounce: Vec<Debounce>,
+    #[serde(default)]
     pub keypress_delay_ms: u64,
     #[serde(default)]
     pub throttle_ms: u64,
+    #[serde(default = "default_relative_release_ms")]
+    pub relative_release_ms: u64,
     #[serde(default)]
     pub config_watch_debounce_ms: u64,
     #[serde(default)]
@@ -63,6 +113,13 @@ pub struct Config {
     pub keymap_table: HashMap<Key, Vec<KeymapEntry>>,
     #[serde(default = "const_true")]
     pub enable_wheel: bool,
+    #[serde(default)]
+    pub enable_gamepad: bool,
+    pub split_output: Option<SplitOutput>,
+    #[serde(default)]
+    pub output_per_device: bool,
+    #[serde(default)]
+    pub sync_leds: bool,
 }
 
 enum ConfigFiletype {
@@ -118,6 +175,10 @@ fn default_mode() -> String {
     "default".to_string()
 }
 
+fn default_relative_release_ms() -> u64 {
+    100
+}
+
 fn deserialize_keys<'de, D>(deserializer: D) -> Result<Vec<Key>, D::Error>
 where
     D: Deserializer<'de>,
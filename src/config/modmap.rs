@@ -2,6 +2,7 @@ use super::device::DeviceMatcher;
 use crate::config::application::deserialize_string_or_vec;
 use crate::config::application::OnlyOrNot;
 use crate::config::key::deserialize_key;
+use crate::config::led::LockMatcher;
 use crate::config::modmap_operator::ModmapOperator;
 use evdev::KeyCode as Key;
 use serde::{Deserialize, Deserializer};
@@ -20,6 +21,7 @@ pub struct Modmap {
     pub device: Option<DeviceMatcher>,
     #[serde(default, deserialize_with = "deserialize_string_or_vec")]
     pub mode: Option<Vec<String>>,
+    pub lock: Option<LockMatcher>,
 }
 
 #[derive(Deserialize, Eq, Hash, PartialEq)]
@@ -12,6 +12,7 @@ use std::time::Duration;
 pub enum ModmapOperator {
     Keys(Keys),
     MultiPurposeKey(MultiPurposeKey),
+    OneShotKey(OneShotKey),
     PressReleaseKey(PressReleaseKey),
 }
 
@@ -40,6 +41,29 @@ pub struct MultiPurposeKey {
     pub free_hold: bool,
     #[serde(default)]
     pub interruptable: Interruptable,
+    // Only choose hold when interrupted by a key on the opposite hand.
+    #[serde(default)]
+    pub bilateral: bool,
+    // Tap then press again within this time to hold the tap-action.
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(default, alias = "quick_tap_term_millis")]
+    pub quick_tap_term: Duration,
+    // Choose tap right away if another key was pressed within this time.
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(default, alias = "require_prior_idle_millis")]
+    pub require_prior_idle: Duration,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+pub struct OneShotKey {
+    // Modifiers that are held for the next key.
+    pub one_shot: Keys,
+    // Mode that is active for the next key.
+    pub mode: Option<String>,
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(default = "default_one_shot_timeout", alias = "timeout_millis")]
+    pub timeout: Duration,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -118,6 +142,10 @@ fn default_tap_timeout() -> Duration {
     Duration::from_millis(1000)
 }
 
+fn default_one_shot_timeout() -> Duration {
+    Duration::from_millis(1000)
+}
+
 fn default_hold_threshold() -> Duration {
     Duration::from_millis(0)
 }
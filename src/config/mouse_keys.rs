@@ -0,0 +1,125 @@
+use super::deserialize_keys;
+use evdev::KeyCode as Key;
+use serde::Deserialize;
+use serde_with::{serde_as, DurationMilliSeconds};
+use std::time::Duration;
+
+// Options for the `{ mouse_move: { x: 1, y: 0 } }` action
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MouseKeys {
+    // Time between each movement of the pointer.
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(default = "default_interval", alias = "interval_millis")]
+    pub interval: Duration,
+    // Pixels per second, when the movement starts.
+    #[serde(default = "default_initial_speed")]
+    pub initial_speed: f64,
+    // Pixels per second, when fully accelerated.
+    #[serde(default = "default_max_speed")]
+    pub max_speed: f64,
+    // Time from initial_speed to max_speed.
+    #[serde_as(as = "DurationMilliSeconds")]
+    #[serde(default = "default_acceleration_time", alias = "acceleration_time_millis")]
+    pub acceleration_time: Duration,
+    // The exponent of the acceleration. 1 is linear.
+    #[serde(default = "default_acceleration_curve")]
+    pub acceleration_curve: f64,
+    // Modifiers that slow the movement down to precision_speed.
+    #[serde(default, deserialize_with = "deserialize_keys")]
+    pub precision_keys: Vec<Key>,
+    #[serde(default = "default_precision_speed")]
+    pub precision_speed: f64,
+    // Notches per second of the `scroll` action.
+    #[serde(default = "default_scroll_speed")]
+    pub scroll_speed: f64,
+    // Pixels of mouse movement per notch of the `drag_scroll` action.
+    #[serde(default = "default_drag_scroll_distance")]
+    pub drag_scroll_distance: f64,
+}
+
+// Direction of the `mouse_move` action
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MouseMove {
+    #[serde(default)]
+    pub x: i32,
+    #[serde(default)]
+    pub y: i32,
+}
+
+// Direction of the `scroll` action. Positive is up and right.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scroll {
+    #[serde(default)]
+    pub vertical: i32,
+    #[serde(default)]
+    pub horizontal: i32,
+}
+
+impl MouseKeys {
+    // Pixels per second, after being held for the given time.
+    pub fn speed(&self, held_for: Duration, precise: bool) -> f64 {
+        if precise {
+            return self.precision_speed;
+        }
+
+        let progress = if self.acceleration_time.is_zero() {
+            1.0
+        } else {
+            (held_for.as_secs_f64() / self.acceleration_time.as_secs_f64()).min(1.0)
+        };
+
+        self.initial_speed + (self.max_speed - self.initial_speed) * progress.powf(self.acceleration_curve)
+    }
+}
+
+impl Default for MouseKeys {
+    fn default() -> Self {
+        MouseKeys {
+            interval: default_interval(),
+            initial_speed: default_initial_speed(),
+            max_speed: default_max_speed(),
+            acceleration_time: default_acceleration_time(),
+            acceleration_curve: default_acceleration_curve(),
+            precision_keys: vec![],
+            precision_speed: default_precision_speed(),
+            scroll_speed: default_scroll_speed(),
+            drag_scroll_distance: default_drag_scroll_distance(),
+        }
+    }
+}
+
+fn default_interval() -> Duration {
+    Duration::from_millis(16)
+}
+
+fn default_initial_speed() -> f64 {
+    200.0
+}
+
+fn default_max_speed() -> f64 {
+    1500.0
+}
+
+fn default_acceleration_time() -> Duration {
+    Duration::from_millis(1000)
+}
+
+fn default_acceleration_curve() -> f64 {
+    2.0
+}
+
+fn default_precision_speed() -> f64 {
+    100.0
+}
+
+fn default_scroll_speed() -> f64 {
+    10.0
+}
+
+fn default_drag_scroll_distance() -> f64 {
+    20.0
+}
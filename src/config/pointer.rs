@@ -0,0 +1,46 @@
+use super::application::OnlyOrNot;
+use super::device::DeviceMatcher;
+use serde::Deserialize;
+
+// Transforms of relative events in `pointer`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pointer {
+    pub device: Option<DeviceMatcher>,
+    pub application: Option<OnlyOrNot>,
+    pub window: Option<OnlyOrNot>,
+    // Multiplier of pointer movement.
+    #[serde(default = "default_one")]
+    pub sensitivity: f64,
+    // Exponent of the pointer movement in each event. 1 is no acceleration.
+    #[serde(default = "default_one")]
+    pub acceleration: f64,
+    #[serde(default)]
+    pub swap_xy: bool,
+    #[serde(default)]
+    pub invert: Vec<Axis>,
+    // Derive the wheel events from only one of the resolutions.
+    pub wheel: Option<WheelResolution>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Axis {
+    X,
+    Y,
+    Wheel,
+    Hwheel,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WheelResolution {
+    // Scroll in notches. The high resolution events are made from the normal ones.
+    LowRes,
+    // The normal events are made from the high resolution ones.
+    HiRes,
+}
+
+fn default_one() -> f64 {
+    1.0
+}
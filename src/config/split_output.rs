@@ -0,0 +1,21 @@
+use serde::Deserialize;
+
+// Separate keyboard and mouse output devices, instead of the combined one
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SplitOutput {
+    #[serde(default)]
+    pub keyboard: OutputDevice,
+    #[serde(default)]
+    pub mouse: OutputDevice,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputDevice {
+    // Appended to the name of the output device, e.g. `xremap: keyboard`
+    pub name: Option<String>,
+    // Defaults to `--vendor` and `--product`
+    pub vendor: Option<u16>,
+    pub product: Option<u16>,
+}
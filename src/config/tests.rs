@@ -1,6 +1,7 @@
 use crate::config::validation::validate_config_file;
 use crate::config::Config;
 use indoc::indoc;
+use std::time::Duration;
 
 #[test]
 fn test_yaml_modmap_basic() {
@@ -64,20 +65,21 @@ fn test_yaml_modmap_multi_purpose_key_with_invalid_key() {
 }
 
 #[test]
-fn test_yaml_modmap_relative_events_are_invalid_1() {
-    assert_invalid_config(
-        indoc! {"
+fn test_yaml_modmap_relative_events_as_input() {
+    let config = indoc! {"
         modmap:
           - remap:
               XUPSCROLL: KEY_A
+              XRIGHTSCROLL:
+                alone: VolumeUp
+                held: Ctrl_L
         "
-        },
-        "Relative mouse events can't be used in modmap",
-    )
+    };
+    validate_config_file(&serde_yaml::from_str::<Config>(config).unwrap()).unwrap();
 }
 
 #[test]
-fn test_yaml_modmap_relative_events_are_invalid_2() {
+fn test_yaml_modmap_relative_events_are_invalid_1() {
     assert_invalid_config(
         indoc! {"
         modmap:
@@ -85,12 +87,12 @@ fn test_yaml_modmap_relative_events_are_invalid_2() {
               KEY_A: XUPSCROLL
         "
         },
-        "Relative mouse events can't be used in modmap",
+        "Relative mouse events can't be used as output in modmap",
     )
 }
 
 #[test]
-fn test_yaml_modmap_relative_events_are_invalid_3() {
+fn test_yaml_modmap_relative_events_are_invalid_2() {
     assert_invalid_config(
         indoc! {"
         modmap:
@@ -100,7 +102,7 @@ fn test_yaml_modmap_relative_events_are_invalid_3() {
                 held: XUPSCROLL
         "
         },
-        "Relative mouse events can't be used in modmap",
+        "Relative mouse events can't be used as output in modmap",
     )
 }
 
@@ -116,6 +118,187 @@ fn test_yaml_modmap_any_key_is_not_valid() {
     )
 }
 
+#[test]
+fn test_yaml_bilateral_key_must_have_a_hand() {
+    assert_invalid_config(
+        indoc! {"
+        hands:
+          left: [a]
+        modmap:
+          - remap:
+              s:
+                held: alt_l
+                alone: s
+                bilateral: true
+        "},
+        "Bilateral multi-purpose key KEY_S must be listed in hands",
+    )
+}
+
+#[test]
+fn test_yaml_tap_dance_needs_hold_for_single_tap() {
+    assert_invalid_config(
+        indoc! {"
+        experimental_map:
+          - remap:
+              a:
+                taps: [b]
+        "},
+        "A tap dance with a single tap action must have a hold action",
+    )
+}
+
+#[test]
+fn test_yaml_tap_dance_more_holds_than_taps_is_invalid() {
+    assert_invalid_config(
+        indoc! {"
+        experimental_map:
+          - remap:
+              a:
+                taps: [b, c]
+                hold: [d, e, f]
+        "},
+        "A tap dance can't have more hold actions than tap actions",
+    )
+}
+
+#[test]
+fn test_yaml_sequence_needs_two_keys() {
+    assert_invalid_config(
+        indoc! {"
+        experimental_map:
+          - sequences:
+              - keys: [j]
+                actions: esc
+        "},
+        "A sequence must have at least two keys",
+    )
+}
+
+#[test]
+fn test_yaml_scroll_needs_enable_wheel() {
+    assert_invalid_config(
+        indoc! {"
+        enable_wheel: false
+        keymap:
+          - remap:
+              a:
+                remap:
+                  b: { scroll: { vertical: 1 } }
+        "},
+        "Scroll actions can't be used with enable_wheel: false",
+    )
+}
+
+#[test]
+fn test_yaml_axis_needs_enable_gamepad() {
+    assert_invalid_config(
+        indoc! {"
+        modmap:
+          - remap:
+              a:
+                press: { axis: { x: -32767 } }
+                release: { axis: { x: 0 } }
+        "},
+        "Axis actions can't be used without enable_gamepad: true",
+    )
+}
+
+#[test]
+fn test_yaml_axis_in_experimental_map_needs_enable_gamepad() {
+    assert_invalid_config(
+        indoc! {"
+        experimental_map:
+          - remap:
+              a:
+                double: { axis: { x: -32767 } }
+        "},
+        "Axis actions can't be used without enable_gamepad: true",
+    )
+}
+
+#[test]
+fn test_yaml_gamepad_release_threshold_above_threshold() {
+    assert_invalid_config(
+        indoc! {"
+        gamepad:
+          threshold: 0.5
+          release_threshold: 0.7
+        "},
+        "gamepad needs 0 < release_threshold <= threshold <= 1",
+    )
+}
+
+#[test]
+fn test_yaml_unknown_gamepad_axis() {
+    let config = indoc! {"
+        enable_gamepad: true
+        keymap:
+          - remap:
+              a: { axis: { ABS_MT_SLOT: 1 } }
+        "};
+    assert!(serde_yaml::from_str::<Config>(config).is_err());
+}
+
+#[test]
+fn test_yaml_split_output() {
+    let config: Config = serde_yaml::from_str(indoc! {"
+        split_output:
+          keyboard:
+            vendor: 0x1234
+            product: 0xabcd
+          mouse:
+            name: pointer
+        "})
+    .unwrap();
+    let split_output = config.split_output.unwrap();
+    assert_eq!(split_output.keyboard.name, None);
+    assert_eq!(split_output.keyboard.vendor, Some(0x1234));
+    assert_eq!(split_output.keyboard.product, Some(0xabcd));
+    assert_eq!(split_output.mouse.name.as_deref(), Some("pointer"));
+    assert_eq!(split_output.mouse.vendor, None);
+}
+
+#[test]
+fn test_yaml_emergency_escape_needs_keys() {
+    assert_invalid_config(
+        indoc! {"
+        emergency_escape:
+          keys: []
+        "},
+        "emergency_escape needs at least one key",
+    )
+}
+
+#[test]
+fn test_yaml_emergency_escape_hold() {
+    let config: Config = serde_yaml::from_str(indoc! {"
+        emergency_escape:
+          hold_millis: 5000
+        "})
+    .unwrap();
+    assert_eq!(config.emergency_escape.hold, Duration::from_secs(5));
+}
+
+#[test]
+fn test_yaml_emergency_escape_hold_needs_millis() {
+    let config = indoc! {"
+        emergency_escape:
+          hold: 3
+        "};
+    assert!(serde_yaml::from_str::<Config>(config).is_err());
+}
+
+#[test]
+fn test_yaml_unknown_led() {
+    let config = indoc! {"
+        led_indicators:
+          - led: shiftlock
+            mark: true
+        "};
+    assert!(serde_yaml::from_str::<Config>(config).is_err());
+}
+
 #[test]
 fn test_yaml_keymap_can_not_emit_relative_events() {
     assert_invalid_config(
@@ -1,3 +1,4 @@
+use crate::config::expmap_operator::{ExpmapAction, ExpmapOperator};
 use crate::config::key_press::KeyPress;
 use crate::config::keymap_action::KeymapAction;
 use crate::config::keymap_action_without_args::ActionWithoutArgs;
@@ -11,8 +12,17 @@ use indexmap::IndexMap;
 pub fn validate_config_file(config: &Config) -> anyhow::Result<()> {
     for modmap in &config.modmap {
         for (key, operator) in &modmap.remap {
-            traverse_modmap_keys(&vec![*key])?;
+            // Relative mouse events are allowed as input.
+            if *key == KEY_MATCH_ANY {
+                bail!("Any-key can't be used in modmap")
+            }
             traverse_modmap_operator(operator)?;
+
+            if let ModmapOperator::MultiPurposeKey(multi_purpose_key) = operator {
+                if multi_purpose_key.bilateral && !config.hands.contains(*key) {
+                    bail!("Bilateral multi-purpose key {key:?} must be listed in hands")
+                }
+            }
         }
     }
 
@@ -20,6 +30,126 @@ pub fn validate_config_file(config: &Config) -> anyhow::Result<()> {
         traverse_remap(&keymap.remap)?;
     }
 
+    if !config.enable_wheel {
+        check_no_scroll(config)?;
+    }
+
+    if !config.enable_gamepad {
+        check_no_axis(config)?;
+    }
+
+    let gamepad = &config.gamepad;
+    if !(0.0 < gamepad.release_threshold && gamepad.release_threshold <= gamepad.threshold && gamepad.threshold <= 1.0)
+    {
+        bail!("gamepad needs 0 < release_threshold <= threshold <= 1")
+    }
+
+    if config.emergency_escape.keys.is_empty() {
+        bail!("emergency_escape needs at least one key")
+    }
+
+    for expmap in &config.experimental_map {
+        for chord in &expmap.chords {
+            traverse_expmap_actions(&chord.actions)?;
+        }
+        for sequence in &expmap.sequences {
+            if sequence.keys.len() < 2 {
+                bail!("A sequence must have at least two keys")
+            }
+            traverse_expmap_actions(&sequence.actions)?;
+        }
+        for operator in expmap.remap.values() {
+            check_expmap_operator(operator)?;
+        }
+    }
+
+    Ok(())
+}
+
+// The output device has no wheel without enable_wheel.
+fn check_no_scroll(config: &Config) -> anyhow::Result<()> {
+    let is_scroll = |action: &KeymapAction| matches!(action, KeymapAction::Scroll(_) | KeymapAction::DragScroll(_));
+    if all_actions(config).any(|action| uses_action(action, &is_scroll)) {
+        bail!("Scroll actions can't be used with enable_wheel: false")
+    }
+
+    Ok(())
+}
+
+// The gamepad device is only created with enable_gamepad.
+fn check_no_axis(config: &Config) -> anyhow::Result<()> {
+    let is_axis = |action: &KeymapAction| matches!(action, KeymapAction::Axis(_));
+    if all_actions(config).any(|action| uses_action(action, &is_axis)) {
+        bail!("Axis actions can't be used without enable_gamepad: true")
+    }
+
+    Ok(())
+}
+
+fn all_actions(config: &Config) -> impl Iterator<Item = &KeymapAction> {
+    let modmap_actions = config
+        .modmap
+        .iter()
+        .flat_map(|modmap| modmap.remap.values())
+        .flat_map(|operator| match operator {
+            ModmapOperator::PressReleaseKey(operator) => vec![&operator.press, &operator.repeat, &operator.release],
+            _ => vec![],
+        })
+        .flatten();
+    let keymap_actions = config.keymap.iter().flat_map(|keymap| keymap.remap.values()).flatten();
+    let expmap_actions = config
+        .experimental_map
+        .iter()
+        .flat_map(|expmap| {
+            let chords = expmap.chords.iter().map(|chord| &chord.actions);
+            let sequences = expmap.sequences.iter().map(|sequence| &sequence.actions);
+            let operators = expmap.remap.values().flat_map(|operator| match operator {
+                ExpmapOperator::DoubleTap(double_tap) => vec![&double_tap.actions],
+                ExpmapOperator::TapDance(tap_dance) => tap_dance.taps.iter().chain(&tap_dance.hold).collect(),
+            });
+            chords.chain(sequences).chain(operators)
+        })
+        .flatten()
+        .filter_map(|action| match action {
+            ExpmapAction::Key(_) => None,
+            ExpmapAction::KeymapAction(action) => Some(action),
+        });
+
+    modmap_actions.chain(keymap_actions).chain(expmap_actions)
+}
+
+fn uses_action(action: &KeymapAction, predicate: &impl Fn(&KeymapAction) -> bool) -> bool {
+    match action {
+        KeymapAction::Remap(remap) => remap
+            .remap
+            .values()
+            .flatten()
+            .any(|action| uses_action(action, predicate)),
+        action => predicate(action),
+    }
+}
+
+fn check_expmap_operator(operator: &ExpmapOperator) -> anyhow::Result<()> {
+    match operator {
+        ExpmapOperator::DoubleTap(double_tap) => {
+            traverse_expmap_actions(&double_tap.actions)?;
+        }
+        ExpmapOperator::TapDance(tap_dance) => {
+            if tap_dance.taps.is_empty() {
+                bail!("A tap dance must have at least one tap action")
+            }
+            if tap_dance.hold.len() > tap_dance.taps.len() {
+                bail!("A tap dance can't have more hold actions than tap actions")
+            }
+            if tap_dance.taps.len() == 1 && tap_dance.hold.is_empty() {
+                bail!("A tap dance with a single tap action must have a hold action")
+            }
+            for actions in tap_dance.taps.iter().chain(&tap_dance.hold) {
+                traverse_expmap_actions(actions)?;
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -29,7 +159,7 @@ fn traverse_modmap_keys(keys: &Vec<Key>) -> anyhow::Result<()> {
             bail!("Any-key can't be used in modmap")
         }
         if key.code() >= DISGUISED_EVENT_OFFSETTER {
-            bail!("Relative mouse events can't be used in modmap")
+            bail!("Relative mouse events can't be used as output in modmap")
         }
     }
 
@@ -58,6 +188,9 @@ fn traverse_modmap_operator(operator: &ModmapOperator) -> anyhow::Result<()> {
             traverse_modmap_keys(&multi_purpose_key.tap.clone().into_vec())?;
             traverse_modmap_keys(&multi_purpose_key.hold.clone().into_vec())?;
         }
+        ModmapOperator::OneShotKey(one_shot_key) => {
+            traverse_modmap_keys(&one_shot_key.one_shot.clone().into_vec())?;
+        }
         ModmapOperator::PressReleaseKey(operator) => {
             // This is keymap actions, though in modmap.
             traverse_actions(&operator.press)?;
@@ -69,6 +202,18 @@ fn traverse_modmap_operator(operator: &ModmapOperator) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn traverse_expmap_actions(actions: &[ExpmapAction]) -> anyhow::Result<()> {
+    let actions: Vec<KeymapAction> = actions
+        .iter()
+        .filter_map(|action| match action {
+            ExpmapAction::Key(_) => None,
+            ExpmapAction::KeymapAction(action) => Some(action.clone()),
+        })
+        .collect();
+
+    traverse_actions(&actions)
+}
+
 fn traverse_remap(keymap: &IndexMap<KeyPress, Vec<KeymapAction>>) -> anyhow::Result<()> {
     for (_, actions) in keymap {
         traverse_actions(actions)?;
@@ -0,0 +1,81 @@
+use crate::config::debounce::Debounce;
+use crate::device::InputDeviceInfo;
+use crate::event_handler::{PRESS, RELEASE};
+use evdev::{EventType, InputEvent, KeyCode as Key};
+use log::debug;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Drops key chatter, before the events are given to EventHandler.
+/// A press that comes within the window after the release of the same key on
+/// the same device is dropped, and so are its repeats and release.
+pub struct Debouncer {
+    debounce: Vec<Debounce>,
+    // When a key was last released on a device.
+    released_at: HashMap<(PathBuf, Key), Instant>,
+    // Keys with a dropped press, that are dropped until released.
+    dropped: HashSet<(PathBuf, Key)>,
+}
+
+impl Debouncer {
+    pub fn new(debounce: &[Debounce]) -> Debouncer {
+        Debouncer {
+            debounce: debounce.to_vec(),
+            released_at: HashMap::new(),
+            dropped: HashSet::new(),
+        }
+    }
+
+    pub fn filter_events(
+        &mut self,
+        device: &InputDeviceInfo,
+        events: impl Iterator<Item = InputEvent>,
+    ) -> Vec<InputEvent> {
+        events.filter(|event| self.filter_event(device, event)).collect()
+    }
+
+    // Whether the event is let through.
+    fn filter_event(&mut self, device: &InputDeviceInfo, event: &InputEvent) -> bool {
+        if event.event_type() != EventType::KEY {
+            return true;
+        }
+
+        let key = Key(event.code());
+
+        let Some(debounce) = self
+            .debounce
+            .iter()
+            .find(|debounce| debounce.device.as_ref().is_none_or(|matcher| matcher.matches(device)))
+        else {
+            return true;
+        };
+
+        if !debounce.applies_to(key) {
+            return true;
+        }
+
+        let id = (device.path.clone(), key);
+
+        if event.value() == PRESS {
+            if let Some(released_at) = self.released_at.get(&id) {
+                let elapsed = released_at.elapsed();
+
+                if elapsed < debounce.window {
+                    debug!("Debounce: Dropped chatter of {key:?} on {}, {elapsed:?} after release", device.name);
+                    self.dropped.insert(id);
+                    return false;
+                }
+            }
+            true
+        } else if event.value() == RELEASE {
+            if self.dropped.remove(&id) {
+                return false;
+            }
+            self.released_at.insert(id, Instant::now());
+            true
+        } else {
+            !self.dropped.contains(&id)
+        }
+    }
+}
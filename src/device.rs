@@ -1,8 +1,16 @@
+use crate::config::Config;
+use crate::emergency_escape::EscapeDetector;
+use crate::gamepad::AxisRecognizer;
+use crate::gesture::GestureRecognizer;
 use crate::util::{evdev_enums_to_string, print_table};
 use anyhow::bail;
 use derive_where::derive_where;
 use evdev::uinput::VirtualDevice;
-use evdev::{AttributeSet, BusType, Device, FetchEventsSynced, InputId, KeyCode as Key, RelativeAxisCode};
+use evdev::{
+    AbsInfo, AbsoluteAxisCode, AttributeSet, AttributeSetRef, BusType, Device, EventType, FetchEventsSynced,
+    InputEvent, InputId, KeyCode as Key, LedCode, MiscCode, PropType, RelativeAxisCode, SynchronizationCode,
+    UinputAbsSetup,
+};
 use log::debug;
 use nix::libc::{EBUSY, ENODEV};
 use std::collections::HashMap;
@@ -34,32 +42,267 @@ pub fn output_device(
     product: u16,
     own_device: &str,
 ) -> anyhow::Result<VirtualDevice> {
+    let device = VirtualDevice::builder()?
+        // These are taken from https://docs.rs/evdev/0.12.0/src/evdev/uinput.rs.html#183-188
+        .input_id(InputId::new(bus_type.unwrap_or(BusType::BUS_USB), vendor, product, 0x111))
+        .name(own_device)
+        .with_keys(&output_keys(|_| true))?
+        .with_relative_axes(&output_relative_axes(enable_wheel))?
+        .with_msc(&output_msc())?
+        .build()?;
+    Ok(device)
+}
+
+// Keyboard for `split_output`. It only has the keyboard keys, so it's not mistaken for another device.
+pub fn keyboard_device(
+    bus_type: Option<BusType>,
+    vendor: u16,
+    product: u16,
+    own_device: &str,
+    name: &str,
+) -> anyhow::Result<VirtualDevice> {
+    let device = VirtualDevice::builder()?
+        .input_id(InputId::new(bus_type.unwrap_or(BusType::BUS_USB), vendor, product, 0x111))
+        .name(&extra_device_name(own_device, name))
+        .with_keys(&output_keys(|key| !is_button(key.code())))?
+        .with_msc(&output_msc())?
+        .build()?;
+    Ok(device)
+}
+
+// Mouse for `split_output`, with the relative axes and the buttons. Gamepad buttons go to the gamepad device, if any.
+pub fn mouse_device(
+    bus_type: Option<BusType>,
+    enable_wheel: bool,
+    enable_gamepad: bool,
+    vendor: u16,
+    product: u16,
+    own_device: &str,
+    name: &str,
+) -> anyhow::Result<VirtualDevice> {
+    let device = VirtualDevice::builder()?
+        .input_id(InputId::new(bus_type.unwrap_or(BusType::BUS_USB), vendor, product, 0x111))
+        .name(&extra_device_name(own_device, name))
+        .with_keys(&output_keys(|key| is_button(key.code()) && !(enable_gamepad && is_gamepad_button(key.code()))))?
+        .with_relative_axes(&output_relative_axes(enable_wheel))?
+        .with_msc(&output_msc())?
+        .build()?;
+    Ok(device)
+}
+
+// Output device of `output_per_device`, which has the name, bus type, ids and capabilities of the input device.
+pub fn input_device_output(
+    device: &InputDevice,
+    capabilities: &Capabilities,
+    own_device: &str,
+) -> anyhow::Result<VirtualDevice> {
+    let name = extra_device_name(own_device, device.device_name());
+    let mut builder = VirtualDevice::builder()?
+        .input_id(InputId::new(device.bus_type(), device.vendor(), device.product(), 0x111))
+        .name(&name);
+    if capabilities.keys.iter().next().is_some() {
+        builder = builder.with_keys(&capabilities.keys)?;
+    }
+    if capabilities.relative_axes.iter().next().is_some() {
+        builder = builder.with_relative_axes(&capabilities.relative_axes)?;
+    }
+    if capabilities.msc.iter().next().is_some() {
+        builder = builder.with_msc(&capabilities.msc)?;
+    }
+    Ok(builder.build()?)
+}
+
+// Events an output device of `output_per_device` can emit. Other events go to the normal output device.
+#[derive(Debug, Default)]
+pub struct Capabilities {
+    pub keys: AttributeSet<Key>,
+    pub relative_axes: AttributeSet<RelativeAxisCode>,
+    pub msc: AttributeSet<MiscCode>,
+}
+
+impl Capabilities {
+    pub fn supports(&self, event: &InputEvent) -> bool {
+        match event.event_type() {
+            EventType::KEY => self.keys.contains(Key(event.code())),
+            EventType::RELATIVE => self.relative_axes.contains(RelativeAxisCode(event.code())),
+            EventType::MISC => self.msc.contains(MiscCode(event.code())),
+            _ => false,
+        }
+    }
+}
+
+fn output_keys(filter: impl Fn(Key) -> bool) -> AttributeSet<Key> {
     let mut keys: AttributeSet<Key> = AttributeSet::new();
     for code in Key::KEY_RESERVED.code()..Key::BTN_TRIGGER_HAPPY40.code() {
         let key = Key::new(code);
         let name = format!("{key:?}");
-        if name.starts_with("KEY_") || name.starts_with("BTN_") {
+        if (name.starts_with("KEY_") || name.starts_with("BTN_")) && filter(key) {
             keys.insert(key);
         }
     }
+    keys
+}
+
+// Scan codes of the keys that aren't remapped
+fn output_msc() -> AttributeSet<MiscCode> {
+    let mut misc: AttributeSet<MiscCode> = AttributeSet::new();
+    misc.insert(MiscCode::MSC_SCAN);
+    misc
+}
 
+fn output_relative_axes(enable_wheel: bool) -> AttributeSet<RelativeAxisCode> {
     let mut relative_axes: AttributeSet<RelativeAxisCode> = AttributeSet::new();
     relative_axes.insert(RelativeAxisCode::REL_X);
     relative_axes.insert(RelativeAxisCode::REL_Y);
     if enable_wheel {
         relative_axes.insert(RelativeAxisCode::REL_HWHEEL);
         relative_axes.insert(RelativeAxisCode::REL_WHEEL);
+        relative_axes.insert(RelativeAxisCode::REL_HWHEEL_HI_RES);
+        relative_axes.insert(RelativeAxisCode::REL_WHEEL_HI_RES);
     }
     relative_axes.insert(RelativeAxisCode::REL_MISC);
+    relative_axes
+}
 
-    let device = VirtualDevice::builder()?
-        // These are taken from https://docs.rs/evdev/0.12.0/src/evdev/uinput.rs.html#183-188
-        .input_id(InputId::new(bus_type.unwrap_or(BusType::BUS_USB), vendor, product, 0x111))
-        .name(own_device)
-        .with_keys(&keys)?
-        .with_relative_axes(&relative_axes)?
-        .build()?;
-    Ok(device)
+fn is_wheel(axis: RelativeAxisCode) -> bool {
+    matches!(
+        axis,
+        RelativeAxisCode::REL_WHEEL
+            | RelativeAxisCode::REL_HWHEEL
+            | RelativeAxisCode::REL_WHEEL_HI_RES
+            | RelativeAxisCode::REL_HWHEEL_HI_RES
+    )
+}
+
+// BTN_LEFT to BTN_TASK
+pub fn is_mouse_button(code: u16) -> bool {
+    (Key::BTN_LEFT.code()..=Key::BTN_TASK.code()).contains(&code)
+}
+
+// Buttons of mice, joysticks, gamepads and tablets, the BTN_* codes. The other codes are keyboard keys, KEY_*.
+pub fn is_button(code: u16) -> bool {
+    (Key::BTN_0.code()..=Key::BTN_GEAR_UP.code()).contains(&code)
+        || (Key::BTN_DPAD_UP.code()..=Key::BTN_DPAD_RIGHT.code()).contains(&code)
+        || (Key::BTN_TRIGGER_HAPPY1.code()..=Key::BTN_TRIGGER_HAPPY40.code()).contains(&code)
+}
+
+// Buttons of the gamepad device
+pub fn is_gamepad_button(code: u16) -> bool {
+    (Key::BTN_SOUTH.code()..=Key::BTN_THUMBR.code()).contains(&code)
+        || (Key::BTN_DPAD_UP.code()..=Key::BTN_DPAD_RIGHT.code()).contains(&code)
+}
+
+// Gamepad for the `axis` action and gamepad buttons
+pub fn gamepad_device(vendor: u16, product: u16, own_device: &str) -> anyhow::Result<VirtualDevice> {
+    let mut keys: AttributeSet<Key> = AttributeSet::new();
+    for code in Key::BTN_SOUTH.code()..=Key::BTN_THUMBR.code() {
+        keys.insert(Key(code));
+    }
+    for code in Key::BTN_DPAD_UP.code()..=Key::BTN_DPAD_RIGHT.code() {
+        keys.insert(Key(code));
+    }
+
+    let name = extra_device_name(own_device, "gamepad");
+    let mut builder = VirtualDevice::builder()?
+        .input_id(InputId::new(BusType::BUS_USB, vendor, product, 0x111))
+        .name(&name)
+        .with_keys(&keys)?;
+    for (axis, minimum, maximum) in GAMEPAD_AXES {
+        let info = AbsInfo::new(0, minimum, maximum, 0, 0, 0);
+        builder = builder.with_absolute_axis(&UinputAbsSetup::new(axis, info))?;
+    }
+    Ok(builder.build()?)
+}
+
+// Axes of the gamepad device and their range. They rest at 0.
+pub const GAMEPAD_AXES: [(AbsoluteAxisCode, i32, i32); 8] = [
+    (AbsoluteAxisCode::ABS_X, -32768, 32767),
+    (AbsoluteAxisCode::ABS_Y, -32768, 32767),
+    (AbsoluteAxisCode::ABS_RX, -32768, 32767),
+    (AbsoluteAxisCode::ABS_RY, -32768, 32767),
+    (AbsoluteAxisCode::ABS_Z, 0, 255),
+    (AbsoluteAxisCode::ABS_RZ, 0, 255),
+    (AbsoluteAxisCode::ABS_HAT0X, -1, 1),
+    (AbsoluteAxisCode::ABS_HAT0Y, -1, 1),
+];
+
+// Mirrors the absolute axes of a touchpad or another absolute device. Remapped buttons go to the output device.
+fn mirror_device(device: &Device, own_device: &str) -> anyhow::Result<VirtualDevice> {
+    let name = extra_device_name(own_device, device.name().unwrap_or_default());
+
+    let mut builder = VirtualDevice::builder()?
+        .input_id(device.input_id())
+        .name(&name)
+        .with_properties(device.properties())?;
+    if let Some(keys) = device.supported_keys() {
+        // Touchpads only emit touches and buttons on the mirror
+        let keys: AttributeSet<Key> = if is_touchpad(Some(keys), device.supported_absolute_axes(), device.properties())
+        {
+            keys.iter()
+                .filter(|key| {
+                    is_mouse_button(key.code()) || is_mirrored(&InputEvent::new(EventType::KEY.0, key.code(), 0))
+                })
+                .collect()
+        } else {
+            keys.iter().collect()
+        };
+        builder = builder.with_keys(&keys)?;
+    }
+    if let Some(misc) = device.misc_properties() {
+        builder = builder.with_msc(misc)?;
+    }
+    for (code, info) in device.get_absinfo()? {
+        builder = builder.with_absolute_axis(&UinputAbsSetup::new(code, info))?;
+    }
+    Ok(builder.build()?)
+}
+
+// Name of the other devices created by xremap, which aren't grabbed.
+pub fn extra_device_name(own_device: &str, name: &str) -> String {
+    let mut name = format!("{own_device}: {name}");
+    // uinput limits the length of names
+    while name.len() > 78 {
+        name.pop();
+    }
+    name
+}
+
+// Events that go to the mirror device. Touches are keys like BTN_TOUCH and BTN_TOOL_FINGER.
+pub fn is_mirrored(event: &InputEvent) -> bool {
+    match event.event_type() {
+        EventType::ABSOLUTE | EventType::MISC => true,
+        EventType::KEY => (Key::BTN_TOOL_PEN.code()..=Key::BTN_TOOL_QUADTAP.code()).contains(&event.code()),
+        _ => false,
+    }
+}
+
+// Touchpad buttons that aren't remapped stay on the mirror, in the same frame as the touches.
+// Clickpads need that for software buttons and clickfinger.
+pub fn is_mirrored_button(event: &InputEvent, config: &Config) -> bool {
+    event.event_type() == EventType::KEY && is_mouse_button(event.code()) && !is_remapped(Key(event.code()), config)
+}
+
+// Whether any modmap, keymap or experimental_map has the key as a trigger
+fn is_remapped(key: Key, config: &Config) -> bool {
+    config.modmap.iter().any(|modmap| modmap.remap.contains_key(&key))
+        || config.keymap_table.contains_key(&key)
+        || config.virtual_modifiers.contains(&key)
+        || config.experimental_map.iter().any(|expmap| {
+            expmap.remap.contains_key(&key)
+                || expmap.chords.iter().any(|chord| chord.keys.contains(&key))
+                || expmap.sequences.iter().any(|sequence| sequence.keys.contains(&key))
+        })
+}
+
+// Touchpads report fingers on multi-touch axes. Touchscreens do too, but they point directly at the screen.
+pub fn is_touchpad(
+    keys: Option<&AttributeSetRef<Key>>,
+    axes: Option<&AttributeSetRef<AbsoluteAxisCode>>,
+    properties: &AttributeSetRef<PropType>,
+) -> bool {
+    keys.is_some_and(|keys| keys.contains(Key::BTN_TOOL_FINGER))
+        && axes.is_some_and(|axes| axes.contains(AbsoluteAxisCode::ABS_MT_POSITION_X))
+        && !properties.contains(PropType::DIRECT)
 }
 
 // We can't know the device path from evdev::enumerate(). So we re-implement it.
@@ -77,6 +320,7 @@ pub fn select_input_devices(
     device_opts: &[String],
     ignore_opts: &[String],
     mouse: bool,
+    gamepad: bool,
     watch: bool,
     own_device: &str,
 ) -> anyhow::Result<HashMap<PathBuf, InputDevice>> {
@@ -93,11 +337,13 @@ pub fn select_input_devices(
     println!("{SEPARATOR}");
 
     if device_opts.is_empty() {
-        if mouse {
-            print!("Selected keyboards and mice automatically since --device options weren't specified");
-        } else {
-            print!("Selected keyboards automatically since --device options weren't specified");
-        }
+        let kinds = match (mouse, gamepad) {
+            (false, false) => "keyboards",
+            (true, false) => "keyboards and mice",
+            (false, true) => "keyboards and gamepads",
+            (true, true) => "keyboards, mice and gamepads",
+        };
+        print!("Selected {kinds} automatically since --device options weren't specified");
     } else {
         print!("Selecting devices matching {device_opts:?}");
     };
@@ -110,7 +356,9 @@ pub fn select_input_devices(
 
     let mut selected: Vec<InputDevice> = vec![];
     for mut device in devices.into_iter() {
-        if device.is_input_device(device_opts, ignore_opts, mouse, own_device) && device.grab() {
+        if device.is_input_device(device_opts, ignore_opts, mouse, gamepad, own_device)
+            && device.grab(mouse, gamepad, own_device)
+        {
             device.print();
             selected.push(device)
         }
@@ -135,6 +383,11 @@ pub fn open_device(path: PathBuf) -> Option<InputDevice> {
         .then_some(InputDevice {
             device: Device::open(&path).ok()?,
             path,
+            mirror: None,
+            mirror_buttons: false,
+            gesture_recognizer: GestureRecognizer::default(),
+            escape_detector: EscapeDetector::default(),
+            axis_recognizer: None,
         })
 }
 
@@ -144,6 +397,8 @@ pub struct InputDeviceInfo {
     pub path: PathBuf,
     pub product: u16,
     pub vendor: u16,
+    // REL_WHEEL_HI_RES and REL_HWHEEL_HI_RES, if the device has them
+    pub hi_res_wheels: Vec<RelativeAxisCode>,
 }
 
 impl InputDeviceInfo {
@@ -225,6 +480,19 @@ pub struct InputDevice {
     path: PathBuf,
     #[derive_where(skip)]
     device: Device,
+    // Output of the absolute axes, if the device has any
+    #[derive_where(skip)]
+    mirror: Option<VirtualDevice>,
+    // Whether buttons that aren't remapped are mirrored, for touchpads
+    #[derive_where(skip)]
+    mirror_buttons: bool,
+    #[derive_where(skip)]
+    gesture_recognizer: GestureRecognizer,
+    // Only for gamepads
+    #[derive_where(skip)]
+    axis_recognizer: Option<AxisRecognizer>,
+    #[derive_where(skip)]
+    escape_detector: EscapeDetector,
 }
 
 impl Eq for InputDevice {}
@@ -277,13 +545,13 @@ impl InputDevice {
         false
     }
 
-    pub fn grab(&mut self) -> bool {
+    pub fn grab(&mut self, mouse: bool, gamepad: bool, own_device: &str) -> bool {
         if !self.wait_for_all_keys_up() {
             return false;
         }
 
         match self.device.grab() {
-            Ok(_) => true,
+            Ok(_) => self.create_mirror(mouse, gamepad, own_device),
             Err(err) if err.raw_os_error() == Some(ENODEV) => {
                 // There's no point of printing errors when devices don't exist, because
                 // this function is only called just after the information is received, that the device
@@ -306,6 +574,131 @@ impl InputDevice {
         }
     }
 
+    // Only touchpads and gamepads are mirrored. Other absolute axes, like those of tablets, are dropped.
+    fn create_mirror(&mut self, mouse: bool, gamepad: bool, own_device: &str) -> bool {
+        let mirrored = (mouse && self.is_touchpad()) || (gamepad && self.is_gamepad());
+        if !mirrored {
+            return true;
+        }
+
+        match mirror_device(&self.device, own_device) {
+            Ok(mirror) => {
+                self.mirror = Some(mirror);
+                self.mirror_buttons = self.is_touchpad();
+                if self.is_gamepad() {
+                    self.axis_recognizer = self.device.get_absinfo().ok().map(AxisRecognizer::new);
+                }
+                true
+            }
+            Err(error) => {
+                eprintln!("warning: Failed to mirror absolute device '{}': {error}", self.device_name());
+                self.ungrab();
+                false
+            }
+        }
+    }
+
+    /// Whether the raw events complete `emergency_escape`.
+    pub fn is_emergency_escape(&mut self, events: &[InputEvent], config: &Config) -> bool {
+        self.escape_detector.on_events(events, &config.emergency_escape)
+    }
+
+    /// Whether `emergency_escape` has been held long enough, checked on the timeout.
+    pub fn is_emergency_escape_held(&self, config: &Config) -> bool {
+        self.escape_detector.is_held(&config.emergency_escape)
+    }
+
+    /// The timeout to set, when the keys of `emergency_escape` are all held.
+    pub fn emergency_escape_timeout(&mut self, config: &Config) -> Option<Duration> {
+        self.escape_detector.take_timeout(&config.emergency_escape)
+    }
+
+    /// Emits the absolute events and touchpad buttons on the mirror device, and returns the other events
+    /// followed by the pseudo-keys of gestures and gamepad axes.
+    pub fn mirror_events(&mut self, events: Vec<InputEvent>, config: &Config) -> Vec<InputEvent> {
+        let Some(mirror) = &mut self.mirror else {
+            return events;
+        };
+
+        let mut frame = vec![];
+        let mut rest = vec![];
+        let mut gesture_keys = vec![];
+        for event in events {
+            gesture_keys.extend(self.gesture_recognizer.on_event(&event, &config.gestures));
+            if let Some(axis_recognizer) = &mut self.axis_recognizer {
+                gesture_keys.extend(axis_recognizer.on_event(&event, &config.gamepad));
+            }
+            if is_mirrored(&event) || (self.mirror_buttons && is_mirrored_button(&event, config)) {
+                frame.push(event);
+            } else {
+                if event.event_type() == EventType::SYNCHRONIZATION
+                    && event.code() == SynchronizationCode::SYN_REPORT.0
+                    && !frame.is_empty()
+                {
+                    // The mirror gets its own SYN_REPORT
+                    let frame = self
+                        .gesture_recognizer
+                        .mirror_frame(std::mem::take(&mut frame), &config.gestures);
+                    if !frame.is_empty() {
+                        if let Err(error) = mirror.emit(&frame) {
+                            eprintln!(
+                                "Failed to mirror events of '{}': {error}",
+                                self.device.name().unwrap_or_default()
+                            );
+                        }
+                    }
+                }
+                rest.push(event);
+            }
+        }
+        rest.extend(gesture_keys);
+        rest
+    }
+
+    // The keys, relative axes and scan codes of the device. Wheels are left out without `enable_wheel`.
+    pub fn capabilities(&self, enable_wheel: bool) -> Capabilities {
+        let mut relative_axes = AttributeSet::new();
+        for axis in self.device.supported_relative_axes().unwrap_or_default() {
+            if enable_wheel || !is_wheel(axis) {
+                relative_axes.insert(axis);
+            }
+        }
+        Capabilities {
+            keys: self
+                .device
+                .supported_keys()
+                .map(|keys| keys.iter().collect())
+                .unwrap_or_default(),
+            relative_axes,
+            msc: self
+                .device
+                .misc_properties()
+                .map(|msc| msc.iter().collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn leds_on(&self) -> Vec<LedCode> {
+        match self.device.get_led_state() {
+            Ok(leds) => leds.iter().collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    pub fn set_leds(&mut self, events: &[InputEvent]) {
+        let Some(supported) = self.device.supported_leds() else {
+            return;
+        };
+        let events: Vec<InputEvent> = events
+            .iter()
+            .filter(|event| supported.contains(LedCode(event.code())))
+            .cloned()
+            .collect();
+        if let Err(error) = self.device.send_events(&events) {
+            eprintln!("Failed to set LEDs of '{}': {error}", self.device_name());
+        }
+    }
+
     pub fn ungrab(&mut self) {
         if let Err(error) = self.device.ungrab() {
             println!("Failed to ungrab device '{}' at '{}' due to: {error}", self.device_name(), self.path.display());
@@ -338,6 +731,17 @@ impl InputDevice {
             product: self.product(),
             vendor: self.vendor(),
             path: self.path.clone(),
+            hi_res_wheels: self
+                .device
+                .supported_relative_axes()
+                .map(|axes| {
+                    axes.iter()
+                        .filter(|axis| {
+                            matches!(*axis, RelativeAxisCode::REL_WHEEL_HI_RES | RelativeAxisCode::REL_HWHEEL_HI_RES)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
         }
     }
 
@@ -346,13 +750,14 @@ impl InputDevice {
         device_filter: &[String],
         ignore_filter: &[String],
         mouse: bool,
+        gamepad: bool,
         own_device: &str,
     ) -> bool {
-        if self.device_name() == own_device {
+        if self.device_name() == own_device || self.device_name().starts_with(&extra_device_name(own_device, "")) {
             return false;
         }
         (if device_filter.is_empty() {
-            self.is_keyboard() || (mouse && self.is_mouse())
+            self.is_keyboard() || (mouse && self.is_mouse()) || (gamepad && self.is_gamepad())
         } else {
             self.matches_any(device_filter)
         }) && (ignore_filter.is_empty() || !self.matches_any(ignore_filter))
@@ -370,9 +775,10 @@ impl InputDevice {
         }
     }
 
+    // Touchpads are included, their absolute axes are mirrored.
     fn is_mouse(&self) -> bool {
-        // Xremap doesn't support absolute device so will break them.
-        if self.device.supported_absolute_axes().is_some() {
+        // Xremap doesn't support other absolute devices so will break them.
+        if self.device.supported_absolute_axes().is_some() && !self.is_touchpad() {
             debug!("Ignoring absolute device {:18} {}", self.path.display(), self.device_name());
             return false;
         }
@@ -381,6 +787,16 @@ impl InputDevice {
             .is_some_and(|keys| keys.contains(Key::BTN_LEFT))
     }
 
+    fn is_touchpad(&self) -> bool {
+        is_touchpad(self.device.supported_keys(), self.device.supported_absolute_axes(), self.device.properties())
+    }
+
+    fn is_gamepad(&self) -> bool {
+        self.device
+            .supported_keys()
+            .is_some_and(|keys| keys.contains(Key::BTN_SOUTH) || keys.contains(Key::BTN_TRIGGER))
+    }
+
     pub fn print(&self) {
         println!("{:18}: {}", self.path.display(), self.device_name())
     }
@@ -0,0 +1,51 @@
+use crate::config::emergency_escape::EmergencyEscape;
+use evdev::{EventType, InputEvent};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+// Recognizes `emergency_escape` on the raw events of a device, before anything can remap them.
+// The keys are checked again on a timeout, so it doesn't depend on key repeat.
+#[derive(Default)]
+pub struct EscapeDetector {
+    pressed: HashSet<u16>,
+    // When all keys were first held together
+    held_at: Option<Instant>,
+    // Whether the timeout for `held_at` still has to be set
+    needs_timeout: bool,
+}
+
+impl EscapeDetector {
+    // Whether the keys have been held long enough
+    pub fn on_events(&mut self, events: &[InputEvent], config: &EmergencyEscape) -> bool {
+        for event in events {
+            if event.event_type() != EventType::KEY {
+                continue;
+            }
+            if event.value() == 0 {
+                self.pressed.remove(&event.code());
+            } else {
+                self.pressed.insert(event.code());
+            }
+        }
+
+        if !config.keys.iter().all(|key| self.pressed.contains(&key.code())) {
+            self.held_at = None;
+            return false;
+        }
+        if self.held_at.is_none() {
+            self.held_at = Some(Instant::now());
+            self.needs_timeout = true;
+        }
+        self.is_held(config)
+    }
+
+    // Whether the keys have been held long enough, also without new events
+    pub fn is_held(&self, config: &EmergencyEscape) -> bool {
+        self.held_at.is_some_and(|held_at| held_at.elapsed() >= config.hold)
+    }
+
+    // The timeout to check the keys again, once after they're all held
+    pub fn take_timeout(&mut self, config: &EmergencyEscape) -> Option<Duration> {
+        std::mem::take(&mut self.needs_timeout).then_some(config.hold)
+    }
+}
@@ -84,6 +84,10 @@ impl EmitHandler {
         result
     }
 
+    pub fn physical_modifiers(&self) -> &[Key] {
+        &self.physical_modifiers
+    }
+
     pub fn on_event(&mut self, event: &Event) {
         update_modifier_state(&mut self.physical_modifiers, event)
     }
@@ -1,3 +1,4 @@
+use crate::config::keymap_action::KeymapAction;
 use crate::device::InputDeviceInfo;
 use crate::event_handler::DISGUISED_EVENT_OFFSETTER;
 use evdev::{EventType, InputEvent, KeyCode as Key};
@@ -16,6 +17,8 @@ pub enum Event {
     OverrideTimeout,
     // Ticks for operators
     Tick,
+    // Action emitted by an operator, which is dispatched like a keymap action
+    KeymapAction(KeymapAction),
 }
 
 impl Event {
@@ -1,16 +1,24 @@
 use crate::action::Action;
 use crate::client::WMClient;
+use crate::config::hands::Hands;
 use crate::config::key_press::{KeyPress, Modifier};
 use crate::config::keymap::{build_override_table, OverrideEntry};
 use crate::config::keymap_action::KeymapAction;
 use crate::config::keymap_action_without_args::ActionWithoutArgs;
-use crate::config::modmap_operator::{Interruptable, Keys, ModmapOperator, MultiPurposeKey, PressReleaseKey};
+use crate::config::modmap_operator::{
+    Interruptable, Keys, ModmapOperator, MultiPurposeKey, OneShotKey, PressReleaseKey,
+};
 use crate::config::nested_remap::Remap;
+use crate::config::pointer::Pointer;
 use crate::config::Config;
 use crate::device::InputDeviceInfo;
 use crate::event::{Event, KeyEvent, RelativeEvent};
+use crate::input_filter::InputFilter;
+use crate::mouse_keys::MouseKeysState;
 use crate::operator_handler::OperatorHandler;
-use evdev::KeyCode as Key;
+use crate::pointer::PointerState;
+use crate::timeout_manager::TimeoutManager;
+use evdev::{AbsoluteAxisCode, EventType, InputEvent, KeyCode as Key, LedCode, MiscCode};
 use log::{debug, warn};
 use nix::sys::time::TimeSpec;
 use nix::sys::timerfd::{Expiration, TimerFd, TimerSetTimeFlags};
@@ -35,24 +43,50 @@ pub struct EventHandler {
     pressed_keys: HashMap<Key, Key>,
     // State machine for multi-purpose keys
     multi_purpose_keys: HashMap<Key, MultiPurposeKeyState>,
+    // When multi-purpose keys were last released as a tap, for quick_tap_term
+    multi_purpose_tapped_at: HashMap<Key, Instant>,
+    // When a key was last pressed, for require_prior_idle
+    last_press_at: Option<Instant>,
+    // State machine for one-shot keys
+    one_shot_keys: HashMap<Key, OneShotKeyState>,
+    // Mode to go back to, when the key that used a one-shot mode has been handled
+    one_shot_mode_to_restore: Option<String>,
     // Current nested remaps
     override_remaps: Vec<HashMap<Key, Vec<OverrideEntry>>>,
     // Key triggered on a timeout of nested remaps
     override_timeout_key: Option<Vec<Key>>,
     // Trigger a timeout of nested remaps through select(2)
     override_timer: TimerFd,
+    // Ticks for mouse keys
+    timeout_manager: Rc<TimeoutManager>,
     // { set_mode: String }
     mode: String,
     // { set_mark: true }
     mark_set: bool,
     // { escape_next_key: true }
     escape_next_key: bool,
+    // { caps_word: true }, and when the word was last continued
+    caps_word_at: Option<Instant>,
+    // { mouse_move: { x: 1, y: 0 } }
+    mouse_keys: MouseKeysState,
+    // { axis: { ABS_X: -32767 } }, by the key that moved the axes
+    held_axes: HashMap<Key, Vec<AbsoluteAxisCode>>,
+    // Relative events in modmap that are pressed as keys, and when they were last seen
+    relative_keys: HashMap<Key, (Rc<InputDeviceInfo>, Instant)>,
+    // Remainders of the transforms in `pointer`
+    pointer: PointerState,
+    // Lock keys that are on, for `lock` in keymap and modmap
+    locks: HashSet<LedCode>,
+    // MSC_SCAN of the next key event, and then the key it belongs to
+    scan: Option<(Option<Key>, InputEvent)>,
     // keypress_delay_ms
     keypress_delay: Duration,
     // Buffered actions to be dispatched. TODO: Just return actions from each function instead of using this.
     actions: Vec<Action>,
     // Handler to perform some of the remapping
     operator_handler: Option<OperatorHandler>,
+    // Slow keys and bounce keys
+    input_filter: Option<InputFilter>,
 }
 
 struct TaggedActions {
@@ -73,39 +107,102 @@ impl AsFd for EventHandler {
 impl EventHandler {
     pub fn new(
         override_timer: TimerFd,
+        timeout_manager: Rc<TimeoutManager>,
         mode: &str,
         keypress_delay: Duration,
         operator_handler: Option<OperatorHandler>,
+        input_filter: Option<InputFilter>,
     ) -> EventHandler {
         EventHandler {
             modifiers: vec![],
             pressed_keys: HashMap::new(),
             multi_purpose_keys: HashMap::new(),
+            multi_purpose_tapped_at: HashMap::new(),
+            last_press_at: None,
+            one_shot_keys: HashMap::new(),
+            one_shot_mode_to_restore: None,
             override_remaps: vec![],
             override_timeout_key: None,
             override_timer,
+            timeout_manager,
             mode: mode.to_string(),
             mark_set: false,
             escape_next_key: false,
+            caps_word_at: None,
+            mouse_keys: MouseKeysState::new(),
+            held_axes: HashMap::new(),
+            relative_keys: HashMap::new(),
+            pointer: PointerState::new(),
+            locks: HashSet::new(),
+            scan: None,
             keypress_delay,
             actions: vec![],
             operator_handler,
+            input_filter,
         }
     }
 
-    // Handle an Event and return Actions. This should be the only public method of EventHandler.
+    pub fn mode(&self) -> &str {
+        &self.mode
+    }
+
+    pub fn mark_set(&self) -> bool {
+        self.mark_set
+    }
+
+    // Forget the held keys, after the output devices released them. They count again when pressed again.
+    pub fn release_held_keys(&mut self) {
+        self.modifiers.clear();
+        self.pressed_keys.clear();
+        self.multi_purpose_keys.clear();
+        self.one_shot_keys.clear();
+    }
+
+    pub fn set_lock(&mut self, led: LedCode, on: bool) {
+        if on {
+            self.locks.insert(led);
+        } else {
+            self.locks.remove(&led);
+        }
+    }
+
+    // Handle an Event and return Actions. The other public methods only share state with the main loop.
     pub fn on_events(
         &mut self,
         mut events: Vec<Event>,
         config: &Config,
         wmclient: &mut WMClient,
     ) -> Result<Vec<Action>, Box<dyn Error>> {
+        if let Some(filter) = &mut self.input_filter {
+            events = filter.filter_events(events);
+        };
+
+        if !config.pointer.is_empty() {
+            events = self.apply_pointer(config, events, wmclient);
+        }
+
+        // Operators consume the ticks, so check them before.
+        let tick = events.iter().any(|event| matches!(event, Event::Tick));
+
         if let Some(handler) = &mut self.operator_handler {
             wmclient.clear_app_class_and_title();
-            events = handler.map_events(events, wmclient);
+            events = handler.map_events(events, wmclient, &self.mode);
         };
 
         debug_assert!(self.actions.is_empty());
+
+        if tick {
+            self.move_mouse(config);
+            if !self.one_shot_keys.is_empty() {
+                self.release_one_shot_keys_on_tick(config);
+            }
+            if !self.relative_keys.is_empty() {
+                let mut released = self.release_relative_keys(config);
+                released.extend(events);
+                events = released;
+            }
+        }
+
         // a vector to collect mouse movement events to be able to send them all at once as one MouseMovementEventCollection.
         let mut mouse_movement_collection: Vec<RelativeEvent> = Vec::new();
         for event in events {
@@ -115,6 +212,20 @@ impl EventHandler {
                 debug!("=> {}: {:?}", key_event.value(), &key_event.key);
             }
 
+            // The scan code is only emitted with the key it belongs to, so remapped keys don't get it.
+            match &event {
+                Event::OtherEvents(event) if is_scan(event) => {
+                    self.scan = Some((None, *event));
+                    continue;
+                }
+                Event::KeyEvent(_, key_event) => {
+                    if let Some((None, scan)) = self.scan.take() {
+                        self.scan = Some((Some(key_event.key), scan));
+                    }
+                }
+                _ => {}
+            }
+
             // Apply modmap
             let modmap_events = self.apply_modmap(config, event, wmclient)?;
 
@@ -125,6 +236,13 @@ impl EventHandler {
                         self.on_key_event(key_event.key, key_event.value(), &device, config, wmclient)?;
                     }
                     Event::RelativeEvent(device, relative_event) => {
+                        if let Some(events) = self.mouse_keys.drag_scroll(&relative_event, &config.mouse_keys) {
+                            if !events.is_empty() {
+                                self.send_action(Action::MouseMovementEventCollection(events));
+                            }
+                            continue;
+                        }
+
                         let key = Key(relative_event.to_disguised_key());
 
                         // Send as disguised-event
@@ -140,13 +258,29 @@ impl EventHandler {
                         }
                     }
 
-                    Event::OtherEvents(event) => self.send_action(Action::InputEvent(event)),
+                    Event::OtherEvents(event) => {
+                        // LED events of grabbed devices only echo what xremap set, e.g. led_indicators,
+                        // so the locks aren't taken from them.
+                        self.send_action(Action::InputEvent(event));
+                    }
                     Event::OverrideTimeout => self.timeout_override()?,
                     Event::Tick => {
                         // Can be ignored. It's for operators.
                     }
+                    Event::KeymapAction(action) => {
+                        // There's no trigger key to emit on timeout of a nested remap,
+                        // so it's handled like a modifier trigger.
+                        self.dispatch_action(&action, &Key::KEY_RESERVED, true, &HashSet::new(), true)?;
+                    }
                 }
             }
+
+            if let Some(mode) = self.one_shot_mode_to_restore.take() {
+                self.mode = mode;
+            }
+            if matches!(self.scan, Some((Some(_), _))) {
+                self.scan = None;
+            }
         }
         // if there is at least one mouse movement event, sending all of them as one MouseMovementEventCollection
         if !mouse_movement_collection.is_empty() {
@@ -166,6 +300,11 @@ impl EventHandler {
         wmclient: &mut WMClient,
     ) -> Result<bool, Box<dyn Error>> {
         let mod_trigger = config.virtual_modifiers.contains(&key) || MODIFIER_KEYS.contains(&key);
+        let caps_word_shift = is_pressed(value) && !mod_trigger && self.continue_caps_word(key, config);
+        if value == RELEASE {
+            self.mouse_keys.stop(key);
+            self.reset_axes(key);
+        }
         // Apply keymap
         let mut matched = false;
         if is_pressed(value) {
@@ -194,13 +333,101 @@ impl EventHandler {
             self.send_key(&key, value);
         } else if !matched {
             // Normal keys are only sent if not matching.
-            self.send_key(&key, value);
+            if caps_word_shift && !self.modifiers.iter().any(|key| SHIFT_KEYS.contains(key)) {
+                self.send_key(&Key::KEY_LEFTSHIFT, PRESS);
+                self.send_key(&key, value);
+                self.send_key(&Key::KEY_LEFTSHIFT, RELEASE);
+            } else {
+                self.send_key(&key, value);
+            }
         }
 
         // The return value is irrelevant here.
         Ok(true)
     }
 
+    fn move_mouse(&mut self, config: &Config) {
+        if !self.mouse_keys.is_moving() {
+            return;
+        }
+
+        let precise = config
+            .mouse_keys
+            .precision_keys
+            .iter()
+            .any(|key| self.modifiers.contains(key));
+        let events = self.mouse_keys.on_tick(&config.mouse_keys, precise);
+
+        if !events.is_empty() {
+            self.send_action(Action::MouseMovementEventCollection(events));
+        }
+
+        self.set_timeout(config.mouse_keys.interval);
+    }
+
+    // Release the relative events in modmap that didn't come again within relative_release_ms.
+    fn release_relative_keys(&mut self, config: &Config) -> Vec<Event> {
+        let timeout = Duration::from_millis(config.relative_release_ms);
+        let mut events = vec![];
+        let mut next_timeout: Option<Duration> = None;
+
+        self.relative_keys.retain(|key, (device, last_at)| {
+            let elapsed = last_at.elapsed();
+            if elapsed < timeout {
+                next_timeout = Some(next_timeout.map_or(timeout - elapsed, |next| next.min(timeout - elapsed)));
+                true
+            } else {
+                events.push(Event::KeyEvent(device.clone(), KeyEvent::new_with(key.code(), RELEASE)));
+                false
+            }
+        });
+
+        if let Some(next_timeout) = next_timeout {
+            self.set_timeout(next_timeout);
+        }
+        events
+    }
+
+    // Axes of the `axis` action go back to rest, when the trigger key is released.
+    fn reset_axes(&mut self, key: Key) {
+        for axis in self.held_axes.remove(&key).unwrap_or_default() {
+            self.send_action(Action::GamepadAxis(axis, 0));
+        }
+    }
+
+    fn set_timeout(&self, timeout: Duration) {
+        if let Err(err) = self.timeout_manager.set_timeout(timeout) {
+            warn!("Failed to set_timeout: {err}");
+        }
+    }
+
+    // Whether caps-word shifts the key. Caps-word ends at a key that doesn't continue the word,
+    // and at shortcuts with modifiers other than shift.
+    fn continue_caps_word(&mut self, key: Key, config: &Config) -> bool {
+        let Some(continued_at) = self.caps_word_at else {
+            return false;
+        };
+
+        if key.code() >= DISGUISED_EVENT_OFFSETTER {
+            // Mouse movement doesn't end the word.
+            return false;
+        }
+
+        let shift = is_letter(&key) || (config.caps_word.underscore && key == Key::KEY_MINUS);
+        let shortcut = self.modifiers.iter().any(|key| !SHIFT_KEYS.contains(key));
+
+        if continued_at.elapsed() < config.caps_word.timeout
+            && !shortcut
+            && (shift || config.caps_word.continue_keys.contains(&key))
+        {
+            self.caps_word_at = Some(Instant::now());
+            shift
+        } else {
+            self.caps_word_at = None;
+            false
+        }
+    }
+
     fn timeout_override(&mut self) -> Result<(), Box<dyn Error>> {
         if let Some(keys) = &self.override_timeout_key.take() {
             for key in keys {
@@ -225,8 +452,20 @@ impl EventHandler {
     }
 
     fn send_key(&mut self, key: &Key, value: i32) {
+        // The LED event from the device comes later, so toggle the lock already.
+        if value == PRESS {
+            if let Some(led) = lock_led(key) {
+                self.set_lock(led, !self.locks.contains(&led));
+            }
+        }
         let event = KeyEvent::new_with(key.code(), value);
-        self.send_action(Action::KeyEvent(event));
+        match self.scan {
+            Some((Some(scan_key), scan)) if scan_key == *key => {
+                self.scan = None;
+                self.send_action(Action::ScannedKeyEvent(event, scan.value()));
+            }
+            _ => self.send_action(Action::KeyEvent(event)),
+        }
     }
 
     fn send_action(&mut self, action: Action) {
@@ -259,6 +498,7 @@ impl EventHandler {
         key_action: ModmapOperator,
         key: Key,
         value: i32,
+        hands: &Hands,
     ) -> Result<Vec<(Key, i32)>, Box<dyn Error>> {
         let keys = match key_action {
             ModmapOperator::Keys(modmap_keys) => modmap_keys
@@ -273,6 +513,9 @@ impl EventHandler {
                 tap_timeout,
                 free_hold,
                 interruptable,
+                bilateral,
+                quick_tap_term,
+                require_prior_idle,
             }) => {
                 match value {
                     PRESS => {
@@ -284,30 +527,47 @@ impl EventHandler {
                             tap_timeout
                         };
 
-                        self.multi_purpose_keys.insert(
-                            key,
-                            MultiPurposeKeyState {
-                                hold,
-                                tap,
-                                interruptable,
-                                hold_threshold_at: if hold_threshold == Duration::ZERO {
-                                    Instant::now()
-                                } else {
-                                    Instant::now() + hold_threshold
-                                },
-                                tap_timeout_at: if free_hold {
-                                    // An approximation of never.
-                                    Instant::now() + Duration::from_secs_f32(1e10)
-                                } else {
-                                    Instant::now() + tap_timeout
-                                },
-                                state: if hold_threshold == Duration::ZERO {
-                                    MultiPurposeKeyStateEnum::HoldPreferred
-                                } else {
-                                    MultiPurposeKeyStateEnum::TapPreferred
-                                },
+                        // Typing or tapped again right after a tap, so the tap-action is held.
+                        let tap_down = self
+                            .multi_purpose_tapped_at
+                            .get(&key)
+                            .is_some_and(|tapped_at| tapped_at.elapsed() < quick_tap_term)
+                            || self
+                                .last_press_at
+                                .is_some_and(|pressed_at| pressed_at.elapsed() < require_prior_idle);
+
+                        let mut state = MultiPurposeKeyState {
+                            hold,
+                            tap,
+                            interruptable,
+                            opposite_hand: bilateral.then(|| hands.opposite_hand(key)),
+                            hold_threshold_at: if hold_threshold == Duration::ZERO {
+                                Instant::now()
+                            } else {
+                                Instant::now() + hold_threshold
                             },
-                        );
+                            tap_timeout_at: if free_hold {
+                                // An approximation of never.
+                                Instant::now() + Duration::from_secs_f32(1e10)
+                            } else {
+                                Instant::now() + tap_timeout
+                            },
+                            state: if hold_threshold == Duration::ZERO {
+                                MultiPurposeKeyStateEnum::HoldPreferred
+                            } else {
+                                MultiPurposeKeyStateEnum::TapPreferred
+                            },
+                        };
+
+                        if tap_down {
+                            state.state = MultiPurposeKeyStateEnum::TapDown;
+                            let mut keys = state.tap.clone().into_vec();
+                            keys.sort_by(modifiers_first);
+                            self.multi_purpose_keys.insert(key, state);
+                            return Ok(keys.into_iter().map(|key| (key, PRESS)).collect());
+                        }
+
+                        self.multi_purpose_keys.insert(key, state);
                         return Ok(vec![]); // delay the press
                     }
                     REPEAT => {
@@ -317,6 +577,11 @@ impl EventHandler {
                     }
                     RELEASE => {
                         if let Some(state) = self.multi_purpose_keys.remove(&key) {
+                            if state.is_tap() {
+                                self.multi_purpose_tapped_at.insert(key, Instant::now());
+                            } else {
+                                self.multi_purpose_tapped_at.remove(&key);
+                            }
                             return Ok(state.release());
                         }
                     }
@@ -325,6 +590,7 @@ impl EventHandler {
                 // fallthrough on state discrepancy
                 vec![(key, value)]
             }
+            ModmapOperator::OneShotKey(one_shot_key) => self.dispatch_one_shot_key(one_shot_key, key, value),
             ModmapOperator::PressReleaseKey(PressReleaseKey {
                 skip_key_event,
                 press,
@@ -393,6 +659,194 @@ impl EventHandler {
         }
     }
 
+    fn dispatch_one_shot_key(&mut self, one_shot_key: OneShotKey, key: Key, value: i32) -> Vec<(Key, i32)> {
+        match value {
+            PRESS => {
+                let mut key_values = vec![];
+
+                if let Some(state) = self.one_shot_keys.remove(&key) {
+                    match state.state {
+                        OneShotKeyStateEnum::Pending { released_at } if released_at.elapsed() < state.timeout => {
+                            // Tapped twice, so it's locked.
+                            self.one_shot_keys.insert(
+                                key,
+                                OneShotKeyState {
+                                    state: OneShotKeyStateEnum::Locked,
+                                    ..state
+                                },
+                            );
+                            return vec![];
+                        }
+                        OneShotKeyStateEnum::Locked => {
+                            let key_values = self.release_one_shot_key(state);
+                            self.one_shot_keys.insert(
+                                key,
+                                OneShotKeyState {
+                                    keys: vec![],
+                                    timeout: one_shot_key.timeout,
+                                    previous_mode: None,
+                                    state: OneShotKeyStateEnum::Unlocked,
+                                },
+                            );
+                            return key_values;
+                        }
+                        _ => {
+                            // Expired, so it starts over.
+                            key_values = self.release_one_shot_key(state);
+                        }
+                    }
+                }
+
+                let keys = one_shot_key.one_shot.into_vec();
+                let previous_mode = one_shot_key.mode.map(|mode| std::mem::replace(&mut self.mode, mode));
+
+                key_values.extend(keys.iter().map(|key| (*key, PRESS)));
+
+                self.one_shot_keys.insert(
+                    key,
+                    OneShotKeyState {
+                        keys,
+                        timeout: one_shot_key.timeout,
+                        previous_mode,
+                        state: OneShotKeyStateEnum::Held { interrupted: false },
+                    },
+                );
+
+                key_values
+            }
+            RELEASE => match self.one_shot_keys.remove(&key) {
+                Some(state) => match state.state {
+                    OneShotKeyStateEnum::Held { interrupted: false } => {
+                        // Tapped, so the next key gets the modifiers.
+                        self.set_timeout(state.timeout);
+                        self.one_shot_keys.insert(
+                            key,
+                            OneShotKeyState {
+                                state: OneShotKeyStateEnum::Pending {
+                                    released_at: Instant::now(),
+                                },
+                                ..state
+                            },
+                        );
+                        vec![]
+                    }
+                    OneShotKeyStateEnum::Held { interrupted: true } => self.release_one_shot_key(state),
+                    OneShotKeyStateEnum::Unlocked => vec![],
+                    OneShotKeyStateEnum::Pending { released_at: _ } | OneShotKeyStateEnum::Locked => {
+                        self.one_shot_keys.insert(key, state);
+                        vec![]
+                    }
+                },
+                None => vec![(key, value)],
+            },
+            _ => vec![], // Repeat isn't meaningful for modifiers.
+        }
+    }
+
+    // Release the modifiers of a one-shot key, and restore the mode right away.
+    fn release_one_shot_key(&mut self, state: OneShotKeyState) -> Vec<(Key, i32)> {
+        if let Some(mode) = state.previous_mode {
+            self.mode = mode;
+        }
+        state.keys.iter().rev().map(|key| (*key, RELEASE)).collect()
+    }
+
+    // A key that isn't a one-shot key is pressed, so pending one-shot keys are used up,
+    // and held one-shot keys act as normal modifiers.
+    fn apply_one_shot_keys(&mut self, key_values: Vec<(Key, i32)>, config: &Config) -> Vec<(Key, i32)> {
+        // Expired one-shot keys are released before anything else.
+        let mut result = self.release_expired_one_shot_keys();
+
+        let is_pressed = key_values.iter().any(|(key, value)| {
+            *value == PRESS && !MODIFIER_KEYS.contains(key) && !config.virtual_modifiers.contains(key)
+        });
+
+        result.extend(key_values);
+
+        if !is_pressed {
+            return result;
+        }
+
+        let pending: Vec<Key> = self
+            .one_shot_keys
+            .iter_mut()
+            .filter_map(|(key, state)| match state.state {
+                OneShotKeyStateEnum::Held { interrupted: _ } => {
+                    state.state = OneShotKeyStateEnum::Held { interrupted: true };
+                    None
+                }
+                OneShotKeyStateEnum::Pending { released_at: _ } => Some(*key),
+                OneShotKeyStateEnum::Locked | OneShotKeyStateEnum::Unlocked => None,
+            })
+            .collect();
+
+        for key in pending {
+            let state = self.one_shot_keys.remove(&key).unwrap();
+
+            // The mode must last until the pressed key has gone through keymap.
+            if let Some(mode) = state.previous_mode {
+                self.one_shot_mode_to_restore.get_or_insert(mode);
+            }
+            result.extend(state.keys.iter().rev().map(|key| (*key, RELEASE)));
+        }
+
+        result
+    }
+
+    // Release the one-shot keys that weren't used within their timeout.
+    fn release_expired_one_shot_keys(&mut self) -> Vec<(Key, i32)> {
+        let expired: Vec<Key> = self
+            .one_shot_keys
+            .iter()
+            .filter_map(|(key, state)| match state.state {
+                OneShotKeyStateEnum::Pending { released_at } if released_at.elapsed() >= state.timeout => Some(*key),
+                _ => None,
+            })
+            .collect();
+
+        let mut key_values = vec![];
+        for key in expired {
+            let state = self.one_shot_keys.remove(&key).unwrap();
+            key_values.extend(self.release_one_shot_key(state));
+        }
+        key_values
+    }
+
+    // The modifiers of one-shot keys are released on timeout, even if no other key comes.
+    fn release_one_shot_keys_on_tick(&mut self, config: &Config) {
+        for (key, value) in self.release_expired_one_shot_keys() {
+            if config.virtual_modifiers.contains(&key) {
+                self.update_modifier(key, value);
+            } else {
+                if MODIFIER_KEYS.contains(&key) {
+                    self.update_modifier(key, value);
+                }
+                self.send_key(&key, value);
+            }
+        }
+    }
+
+    fn apply_pointer(&mut self, config: &Config, events: Vec<Event>, wmclient: &mut WMClient) -> Vec<Event> {
+        let mut result = vec![];
+        for event in events {
+            match event {
+                Event::RelativeEvent(device, relative_event) => {
+                    wmclient.clear_app_class_and_title();
+                    match find_pointer(config, &device, wmclient) {
+                        Some(pointer) => {
+                            for relative_event in self.pointer.transform(pointer, &device, &relative_event) {
+                                result.push(Event::RelativeEvent(device.clone(), relative_event));
+                            }
+                        }
+                        None => result.push(Event::RelativeEvent(device, relative_event)),
+                    }
+                }
+                event => result.push(event),
+            }
+        }
+        result
+    }
+
     fn apply_modmap(
         &mut self,
         config: &Config,
@@ -405,7 +859,7 @@ impl EventHandler {
                 let value = key_event.value();
 
                 let mut key_values = if let Some(key_action) = self.find_modmap(config, &key, &device, wmclient) {
-                    self.dispatch_keys(key_action, key, value)?
+                    self.dispatch_keys(key_action, key, value, &config.hands)?
                 } else {
                     vec![(key, value)]
                 };
@@ -413,6 +867,16 @@ impl EventHandler {
                 if !self.multi_purpose_keys.is_empty() {
                     key_values = self.flush_timeout_keys(key_values);
                 }
+                if !self.one_shot_keys.is_empty() && !self.one_shot_keys.contains_key(&key) {
+                    key_values = self.apply_one_shot_keys(key_values, config);
+                }
+                if value == PRESS {
+                    self.last_press_at = Some(Instant::now());
+                } else if value == RELEASE {
+                    // Stop mouse_move and reset axes of press/release keys
+                    self.mouse_keys.stop(key);
+                    self.reset_axes(key);
+                }
 
                 let events: Vec<_> = key_values
                     .into_iter()
@@ -422,8 +886,23 @@ impl EventHandler {
                 Ok(events)
             }
             Event::RelativeEvent(device, relative_event) => {
+                let key = Key(relative_event.to_disguised_key());
+                if self.relative_keys.contains_key(&key) || self.find_modmap(config, &key, device, wmclient).is_some() {
+                    // Pressed as a key, which is released by `release_relative_keys`.
+                    let value = if let Some((_, last_at)) = self.relative_keys.get_mut(&key) {
+                        *last_at = Instant::now();
+                        REPEAT
+                    } else {
+                        self.relative_keys.insert(key, (device.clone(), Instant::now()));
+                        self.set_timeout(Duration::from_millis(config.relative_release_ms));
+                        PRESS
+                    };
+                    let event = Event::KeyEvent(device.clone(), KeyEvent::new_with(key.code(), value));
+                    return self.apply_modmap(config, event, wmclient);
+                }
+
                 // Can't use `flush_timeout_keys`, because it would also emit the disguised key.
-                let pressed = vec![Key(relative_event.to_disguised_key())];
+                let pressed = vec![key];
 
                 let mut events = vec![];
                 for (_, state) in self.multi_purpose_keys.iter_mut() {
@@ -472,6 +951,11 @@ impl EventHandler {
                         continue;
                     }
                 }
+                if let Some(lock_matcher) = &modmap.lock {
+                    if !lock_matcher.matches(&self.locks) {
+                        continue;
+                    }
+                }
                 return Some(key_action.clone());
             }
         }
@@ -593,6 +1077,11 @@ impl EventHandler {
                                 continue;
                             }
                         }
+                        if let Some(lock_matcher) = &entry.lock {
+                            if !lock_matcher.matches(&self.locks) {
+                                continue;
+                            }
+                        }
 
                         let actions = TaggedActions {
                             actions: entry.actions.clone(),
@@ -692,6 +1181,47 @@ impl EventHandler {
                 self.send_key_press_and_release(&self.with_mark(key_press), extra_modifiers_pressed)
             }
             KeymapAction::EscapeNextKey(escape_next_key) => self.escape_next_key = *escape_next_key,
+            KeymapAction::CapsWord(caps_word) => {
+                self.caps_word_at = if *caps_word { Some(Instant::now()) } else { None };
+            }
+            KeymapAction::MouseMove(direction) => {
+                if *key == Key::KEY_RESERVED {
+                    warn!("mouse_move needs a trigger key to stop the movement");
+                } else if self.mouse_keys.start(*key, direction) {
+                    // Tick right away, and then every interval of mouse_keys.
+                    self.set_timeout(Duration::ZERO);
+                }
+            }
+            KeymapAction::Scroll(direction) => {
+                if *key == Key::KEY_RESERVED {
+                    warn!("scroll needs a trigger key to stop the scrolling");
+                } else if self.mouse_keys.start_scroll(*key, direction) {
+                    self.set_timeout(Duration::ZERO);
+                }
+            }
+            KeymapAction::DragScroll(drag_scroll) => {
+                if !*drag_scroll {
+                    self.mouse_keys.stop_drag_scroll();
+                } else if *key == Key::KEY_RESERVED {
+                    warn!("drag_scroll needs a trigger key to stop the scrolling");
+                } else {
+                    self.mouse_keys.start_drag_scroll(*key);
+                }
+            }
+            KeymapAction::Axis(axes) => {
+                for (axis, value) in &axes.0 {
+                    self.send_action(Action::GamepadAxis(*axis, *value));
+                }
+                // Without a trigger key, the axes stay until moved by another action.
+                if *key != Key::KEY_RESERVED {
+                    let held_axes = self.held_axes.entry(*key).or_default();
+                    for (axis, _) in &axes.0 {
+                        if !held_axes.contains(axis) {
+                            held_axes.push(*axis);
+                        }
+                    }
+                }
+            }
             KeymapAction::Sleep(millis) => self.send_action(Action::Delay(Duration::from_millis(*millis))),
             KeymapAction::CloseByAppClass(app_class) => self.actions.push(Action::CloseByAppClass(app_class.clone())),
             KeymapAction::Action(action) => match action {
@@ -799,6 +1329,21 @@ impl EventHandler {
     }
 }
 
+// The first entry in `pointer` that matches the device, window and application.
+fn find_pointer<'a>(config: &'a Config, device: &InputDeviceInfo, wmclient: &mut WMClient) -> Option<&'a Pointer> {
+    config.pointer.iter().find(|pointer| {
+        pointer.device.as_ref().is_none_or(|matcher| matcher.matches(device))
+            && pointer
+                .window
+                .as_ref()
+                .is_none_or(|matcher| wmclient.match_window(matcher))
+            && pointer
+                .application
+                .as_ref()
+                .is_none_or(|matcher| wmclient.match_application(matcher))
+    })
+}
+
 fn has_remap(actions: &[KeymapAction]) -> bool {
     if actions.is_empty() {
         // When actions are empty it could either be regarded as an empty remap
@@ -843,12 +1388,59 @@ pub static MODIFIER_KEYS: [Key; 8] = [
     Key::KEY_RIGHTMETA,
 ];
 
+static SHIFT_KEYS: [Key; 2] = [Key::KEY_LEFTSHIFT, Key::KEY_RIGHTSHIFT];
+
 // ---
 
+fn is_letter(key: &Key) -> bool {
+    matches!(
+        *key,
+        Key::KEY_A
+            | Key::KEY_B
+            | Key::KEY_C
+            | Key::KEY_D
+            | Key::KEY_E
+            | Key::KEY_F
+            | Key::KEY_G
+            | Key::KEY_H
+            | Key::KEY_I
+            | Key::KEY_J
+            | Key::KEY_K
+            | Key::KEY_L
+            | Key::KEY_M
+            | Key::KEY_N
+            | Key::KEY_O
+            | Key::KEY_P
+            | Key::KEY_Q
+            | Key::KEY_R
+            | Key::KEY_S
+            | Key::KEY_T
+            | Key::KEY_U
+            | Key::KEY_V
+            | Key::KEY_W
+            | Key::KEY_X
+            | Key::KEY_Y
+            | Key::KEY_Z
+    )
+}
+
 fn is_pressed(value: i32) -> bool {
     value == PRESS || value == REPEAT
 }
 
+fn is_scan(event: &InputEvent) -> bool {
+    event.event_type() == EventType::MISC && event.code() == MiscCode::MSC_SCAN.0
+}
+
+fn lock_led(key: &Key) -> Option<LedCode> {
+    match *key {
+        Key::KEY_CAPSLOCK => Some(LedCode::LED_CAPSL),
+        Key::KEY_NUMLOCK => Some(LedCode::LED_NUML),
+        Key::KEY_SCROLLLOCK => Some(LedCode::LED_SCROLLL),
+        _ => None,
+    }
+}
+
 // InputEvent#value
 pub const RELEASE: i32 = 0;
 pub const PRESS: i32 = 1;
@@ -870,6 +1462,8 @@ enum MultiPurposeKeyStateEnum {
     TapChosen,
     // Hold-action has been pressed, but has not been released yet.
     HoldDown,
+    // Tap-action has been pressed, and is released with the multi-purpose key.
+    TapDown,
 }
 
 #[derive(Debug)]
@@ -877,6 +1471,8 @@ struct MultiPurposeKeyState {
     hold: Keys,
     tap: Keys,
     interruptable: Interruptable,
+    // The only keys that choose the hold-action when interrupting, for bilateral multi-purpose keys
+    opposite_hand: Option<Vec<Key>>,
     hold_threshold_at: Instant,
     tap_timeout_at: Instant,
     state: MultiPurposeKeyStateEnum,
@@ -914,6 +1510,22 @@ impl MultiPurposeKeyState {
             MultiPurposeKeyStateEnum::TapChosen => {
                 vec![] // tap-action already released, so ignores repeat
             }
+            MultiPurposeKeyStateEnum::TapDown => {
+                let mut keys = self.tap.clone().into_vec();
+                keys.sort_by(modifiers_first);
+                keys.into_iter().map(|key| (key, REPEAT)).collect()
+            }
+        }
+    }
+
+    // Whether the tap-action is, or will be, emitted on release.
+    fn is_tap(&self) -> bool {
+        match self.state {
+            MultiPurposeKeyStateEnum::TapPreferred
+            | MultiPurposeKeyStateEnum::TapChosen
+            | MultiPurposeKeyStateEnum::TapDown => true,
+            MultiPurposeKeyStateEnum::HoldPreferred => Instant::now() < self.tap_timeout_at,
+            MultiPurposeKeyStateEnum::HoldDown => false,
         }
     }
 
@@ -941,6 +1553,11 @@ impl MultiPurposeKeyState {
             MultiPurposeKeyStateEnum::TapChosen => {
                 vec![] // nothing to release
             }
+            MultiPurposeKeyStateEnum::TapDown => {
+                let mut release_keys = self.tap.clone().into_vec();
+                release_keys.sort_by(modifiers_last);
+                release_keys.into_iter().map(|key| (key, RELEASE)).collect()
+            }
         }
     }
 
@@ -961,6 +1578,16 @@ impl MultiPurposeKeyState {
                 self.state = MultiPurposeKeyStateEnum::TapChosen;
                 self.press_and_release(&self.tap)
             }
+            MultiPurposeKeyStateEnum::HoldPreferred
+                if self
+                    .opposite_hand
+                    .as_ref()
+                    .is_some_and(|opposite_hand| pressed.iter().any(|key| !opposite_hand.contains(key))) =>
+            {
+                // Rolling keys on the same hand is typing.
+                self.state = MultiPurposeKeyStateEnum::TapChosen;
+                self.press_and_release(&self.tap)
+            }
             MultiPurposeKeyStateEnum::HoldPreferred => {
                 self.state = MultiPurposeKeyStateEnum::HoldDown;
 
@@ -968,7 +1595,9 @@ impl MultiPurposeKeyState {
                 keys.sort_by(modifiers_first);
                 keys.into_iter().map(|key| (key, PRESS)).collect()
             }
-            MultiPurposeKeyStateEnum::HoldDown | MultiPurposeKeyStateEnum::TapChosen => vec![],
+            MultiPurposeKeyStateEnum::HoldDown
+            | MultiPurposeKeyStateEnum::TapChosen
+            | MultiPurposeKeyStateEnum::TapDown => vec![],
         }
     }
 
@@ -985,6 +1614,27 @@ impl MultiPurposeKeyState {
     }
 }
 
+#[derive(Debug)]
+enum OneShotKeyStateEnum {
+    // The key is held down. It acts like a normal modifier if another key is pressed.
+    Held { interrupted: bool },
+    // The key has been tapped, so the modifiers are held for the next key.
+    Pending { released_at: Instant },
+    // The key has been tapped twice, so the modifiers are held until it's pressed again.
+    Locked,
+    // The lock has been released, and the release of the key is squashed.
+    Unlocked,
+}
+
+#[derive(Debug)]
+struct OneShotKeyState {
+    keys: Vec<Key>,
+    timeout: Duration,
+    // The mode before the one-shot key was pressed, if it sets a mode.
+    previous_mode: Option<String>,
+    state: OneShotKeyStateEnum,
+}
+
 /// Orders modifier keys ahead of non-modifier keys.
 /// Unfortunately the underlying type doesn't allow direct
 /// comparison, but that's ok for our purposes.
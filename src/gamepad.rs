@@ -0,0 +1,93 @@
+use crate::config::gamepad::Gamepad;
+use crate::event_handler::{DISGUISED_EVENT_OFFSETTER, PRESS, RELEASE};
+use evdev::{AbsInfo, AbsoluteAxisCode, EventType, InputEvent};
+use std::collections::HashMap;
+
+// The pseudo-keys follow the touchpad gestures, see `parse_key`.
+const ABSOLUTE_KEY_OFFSET: u16 = DISGUISED_EVENT_OFFSETTER + 48;
+
+/// The pseudo-key of an axis of a gamepad, from ABS_X to ABS_HAT3Y.
+pub fn absolute_key(axis: AbsoluteAxisCode, positive: bool) -> Option<u16> {
+    if axis.0 > AbsoluteAxisCode::ABS_HAT3Y.0 {
+        return None;
+    }
+    Some(ABSOLUTE_KEY_OFFSET + axis.0 * 2 + if positive { 0 } else { 1 })
+}
+
+/// Presses pseudo-keys when the axes of a gamepad are moved away from rest,
+/// and releases them when the axes are back. Hat switches are axes too.
+pub struct AxisRecognizer {
+    axes: HashMap<u16, Axis>,
+}
+
+struct Axis {
+    // The position when the device was grabbed
+    rest: i32,
+    minimum: i32,
+    maximum: i32,
+    // The pressed direction, true is positive
+    pressed: Option<bool>,
+}
+
+impl AxisRecognizer {
+    pub fn new(axes: impl Iterator<Item = (AbsoluteAxisCode, AbsInfo)>) -> AxisRecognizer {
+        AxisRecognizer {
+            axes: axes
+                .filter(|(axis, _)| absolute_key(*axis, true).is_some())
+                .map(|(axis, info)| {
+                    let axis_state = Axis {
+                        rest: info.value(),
+                        minimum: info.minimum(),
+                        maximum: info.maximum(),
+                        pressed: None,
+                    };
+                    (axis.0, axis_state)
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the presses and releases of the pseudo-keys.
+    pub fn on_event(&mut self, event: &InputEvent, gamepad: &Gamepad) -> Vec<InputEvent> {
+        if event.event_type() != EventType::ABSOLUTE {
+            return vec![];
+        }
+        let Some(axis) = self.axes.get_mut(&event.code()) else {
+            return vec![];
+        };
+        let code = AbsoluteAxisCode(event.code());
+        let deflection = axis.deflection(event.value());
+
+        let mut keys = vec![];
+        if let Some(positive) = axis.pressed {
+            if deflection.is_sign_positive() != positive || deflection.abs() < gamepad.release_threshold {
+                keys.extend(absolute_key(code, positive).map(|key| (key, RELEASE)));
+                axis.pressed = None;
+            }
+        }
+        if axis.pressed.is_none() && deflection.abs() >= gamepad.threshold {
+            let positive = deflection > 0.0;
+            keys.extend(absolute_key(code, positive).map(|key| (key, PRESS)));
+            axis.pressed = Some(positive);
+        }
+
+        keys.into_iter()
+            .map(|(key, value)| InputEvent::new(EventType::KEY.0, key, value))
+            .collect()
+    }
+}
+
+impl Axis {
+    // From -1 to 1, where 0 is rest.
+    fn deflection(&self, value: i32) -> f64 {
+        let range = if value > self.rest {
+            self.maximum - self.rest
+        } else {
+            self.rest - self.minimum
+        };
+        if range == 0 {
+            return 0.0;
+        }
+        (value - self.rest) as f64 / range as f64
+    }
+}
@@ -0,0 +1,192 @@
+use crate::config::gestures::Gestures;
+use crate::event_handler::{DISGUISED_EVENT_OFFSETTER, PRESS, RELEASE};
+use evdev::{AbsoluteAxisCode, EventType, InputEvent, SynchronizationCode};
+use std::collections::{HashMap, HashSet};
+
+// The pseudo-keys follow the disguised relative events, see `parse_key`.
+const XRIGHTSWIPE3: u16 = DISGUISED_EVENT_OFFSETTER + 32;
+const XRIGHTSWIPE4: u16 = DISGUISED_EVENT_OFFSETTER + 36;
+const XPINCHIN: u16 = DISGUISED_EVENT_OFFSETTER + 40;
+const XPINCHOUT: u16 = DISGUISED_EVENT_OFFSETTER + 41;
+
+/// Recognizes swipes and pinches from the multitouch slots of a touchpad.
+/// A gesture is pressed and released as a pseudo-key, when the fingers have
+/// moved far enough. Only one gesture is recognized until all fingers are lifted.
+#[derive(Default)]
+pub struct GestureRecognizer {
+    slot: i32,
+    // Current position of the fingers on the touchpad by slot
+    touches: HashMap<i32, (i32, i32)>,
+    // Positions where the current number of fingers started
+    start: HashMap<i32, (i32, i32)>,
+    recognized: bool,
+    // Swipes are withheld from the mirror device until all fingers are lifted.
+    consuming: bool,
+    // Slots and touch keys like BTN_TOOL_TRIPLETAP, that are down on the mirror device
+    mirror_slot: i32,
+    mirror_slots: HashSet<i32>,
+    mirror_keys: HashSet<u16>,
+}
+
+impl GestureRecognizer {
+    /// Returns the press and release of the gesture, if one was recognized.
+    pub fn on_event(&mut self, event: &InputEvent, gestures: &Gestures) -> Vec<InputEvent> {
+        match (event.event_type(), event.code()) {
+            (EventType::ABSOLUTE, code) => self.on_absolute(AbsoluteAxisCode(code), event.value()),
+            (EventType::SYNCHRONIZATION, code) if code == SynchronizationCode::SYN_REPORT.0 => {
+                if let Some(key) = self.on_frame(gestures) {
+                    return vec![
+                        InputEvent::new(EventType::KEY.0, key, PRESS),
+                        InputEvent::new(EventType::KEY.0, key, RELEASE),
+                    ];
+                }
+            }
+            _ => {}
+        }
+        vec![]
+    }
+
+    /// Returns the events of a frame that go to the mirror device. This must follow `on_event` of
+    /// the frame's SYN_REPORT. With `consume_swipes`, the touches are lifted on the mirror device,
+    /// when a third finger is put down, and the rest of the touch is withheld.
+    pub fn mirror_frame(&mut self, frame: Vec<InputEvent>, gestures: &Gestures) -> Vec<InputEvent> {
+        if gestures.consume_swipes && self.touches.len() >= 3 {
+            self.consuming = true;
+        }
+        if !self.consuming {
+            self.track_mirror(&frame);
+            return frame;
+        }
+        if self.touches.is_empty() {
+            self.consuming = false;
+        }
+        self.lift_mirror()
+    }
+
+    fn track_mirror(&mut self, frame: &[InputEvent]) {
+        for event in frame {
+            match (event.event_type(), event.code()) {
+                (EventType::ABSOLUTE, code) if code == AbsoluteAxisCode::ABS_MT_SLOT.0 => {
+                    self.mirror_slot = event.value()
+                }
+                (EventType::ABSOLUTE, code) if code == AbsoluteAxisCode::ABS_MT_TRACKING_ID.0 => {
+                    if event.value() < 0 {
+                        self.mirror_slots.remove(&self.mirror_slot);
+                    } else {
+                        self.mirror_slots.insert(self.mirror_slot);
+                    }
+                }
+                (EventType::KEY, code) => {
+                    if event.value() == RELEASE {
+                        self.mirror_keys.remove(&code);
+                    } else {
+                        self.mirror_keys.insert(code);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Lift all touches on the mirror device, as if the fingers left the touchpad.
+    fn lift_mirror(&mut self) -> Vec<InputEvent> {
+        if self.mirror_slots.is_empty() && self.mirror_keys.is_empty() {
+            return vec![];
+        }
+        let mut events = vec![];
+        for slot in self.mirror_slots.drain() {
+            events.push(InputEvent::new(EventType::ABSOLUTE.0, AbsoluteAxisCode::ABS_MT_SLOT.0, slot));
+            events.push(InputEvent::new(EventType::ABSOLUTE.0, AbsoluteAxisCode::ABS_MT_TRACKING_ID.0, -1));
+        }
+        for key in self.mirror_keys.drain() {
+            events.push(InputEvent::new(EventType::KEY.0, key, RELEASE));
+        }
+        // The touchpad only reports a change of slot, so the mirror must stay in the same one.
+        events.push(InputEvent::new(EventType::ABSOLUTE.0, AbsoluteAxisCode::ABS_MT_SLOT.0, self.slot));
+        self.mirror_slot = self.slot;
+        events
+    }
+
+    fn on_absolute(&mut self, code: AbsoluteAxisCode, value: i32) {
+        match code {
+            AbsoluteAxisCode::ABS_MT_SLOT => self.slot = value,
+            AbsoluteAxisCode::ABS_MT_TRACKING_ID => {
+                if value < 0 {
+                    self.touches.remove(&self.slot);
+                } else {
+                    self.touches.insert(self.slot, (0, 0));
+                }
+            }
+            AbsoluteAxisCode::ABS_MT_POSITION_X => {
+                if let Some(touch) = self.touches.get_mut(&self.slot) {
+                    touch.0 = value;
+                }
+            }
+            AbsoluteAxisCode::ABS_MT_POSITION_Y => {
+                if let Some(touch) = self.touches.get_mut(&self.slot) {
+                    touch.1 = value;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_frame(&mut self, gestures: &Gestures) -> Option<u16> {
+        if self.touches.len() != self.start.len() || !self.touches.keys().all(|slot| self.start.contains_key(slot)) {
+            // Start over, when a finger is added or lifted.
+            self.start = self.touches.clone();
+            if self.touches.is_empty() {
+                self.recognized = false;
+            }
+            return None;
+        }
+        if self.recognized {
+            return None;
+        }
+
+        let key = match self.touches.len() {
+            2 => self.pinch(gestures.pinch_distance),
+            3 => self.swipe(gestures.swipe_distance, XRIGHTSWIPE3),
+            4 => self.swipe(gestures.swipe_distance, XRIGHTSWIPE4),
+            _ => None,
+        };
+        self.recognized = key.is_some();
+        key
+    }
+
+    // The keys of each finger count are ordered: right, left, down, up.
+    fn swipe(&self, distance: i32, right: u16) -> Option<u16> {
+        let fingers = self.touches.len() as i32;
+        let (dx, dy) = self.touches.iter().fold((0, 0), |(dx, dy), (slot, (x, y))| {
+            let (start_x, start_y) = self.start[slot];
+            (dx + x - start_x, dy + y - start_y)
+        });
+        let (dx, dy) = (dx / fingers, dy / fingers);
+
+        if dx.abs() >= dy.abs() && dx.abs() >= distance {
+            Some(if dx > 0 { right } else { right + 1 })
+        } else if dy.abs() > dx.abs() && dy.abs() >= distance {
+            Some(if dy > 0 { right + 2 } else { right + 3 })
+        } else {
+            None
+        }
+    }
+
+    fn pinch(&self, distance: i32) -> Option<u16> {
+        let spread = |touches: &HashMap<i32, (i32, i32)>| {
+            let mut positions = touches.values();
+            let (x1, y1) = positions.next()?;
+            let (x2, y2) = positions.next()?;
+            Some((((x1 - x2) as f64).powi(2) + ((y1 - y2) as f64).powi(2)).sqrt())
+        };
+        let change = spread(&self.touches)? - spread(&self.start)?;
+
+        if change >= distance as f64 {
+            Some(XPINCHOUT)
+        } else if change <= -distance as f64 {
+            Some(XPINCHIN)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::config::device::DeviceMatcher;
+use crate::config::input_filter::{BounceKeys, SlowKeys};
+use crate::device::InputDeviceInfo;
+use crate::event::{Event, KeyEvent};
+use crate::event_handler::{PRESS, RELEASE};
+use crate::timeout_manager::TimeoutManager;
+use evdev::KeyCode as Key;
+use log::{debug, error};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Filters the physical key events before any remapping.
+///  - Slow keys: The press is held back until the key has been held for the delay.
+///    If the key is released before that, then neither press nor release is emitted.
+///  - Bounce keys: A press is ignored, if the same key was released less than the
+///    delay ago. The repeat and release of the ignored press are ignored too.
+pub struct InputFilter {
+    slow_keys: Vec<SlowKeys>,
+    bounce_keys: Vec<BounceKeys>,
+    timeout_manager: Rc<TimeoutManager>,
+    // Presses held back by slow keys, and when they are accepted.
+    pending: Vec<(Instant, Event)>,
+    // Keys with an ignored press, that must be ignored until released.
+    suppressed: HashSet<Key>,
+    // When keys were last released, for bounce keys.
+    released_at: HashMap<Key, Instant>,
+}
+
+impl InputFilter {
+    pub fn new(slow_keys: &[SlowKeys], bounce_keys: &[BounceKeys], timeout_manager: Rc<TimeoutManager>) -> Self {
+        InputFilter {
+            slow_keys: slow_keys.to_vec(),
+            bounce_keys: bounce_keys.to_vec(),
+            timeout_manager,
+            pending: vec![],
+            suppressed: HashSet::new(),
+            released_at: HashMap::new(),
+        }
+    }
+
+    pub fn filter_events(&mut self, events: Vec<Event>) -> Vec<Event> {
+        let mut result = vec![];
+
+        for event in events {
+            match &event {
+                Event::KeyEvent(device, key_event) => {
+                    // The tick may not have come yet, so a press held long enough must go before this event.
+                    if !self.pending.is_empty() {
+                        result.extend(self.accept_pending());
+                    }
+                    if self.filter_key_event(device, key_event, &event) {
+                        result.push(event);
+                    }
+                }
+                Event::Tick => {
+                    result.extend(self.accept_pending());
+                    result.push(event);
+                }
+                _ => result.push(event),
+            }
+        }
+
+        result
+    }
+
+    // Whether the event is let through.
+    fn filter_key_event(&mut self, device: &Rc<InputDeviceInfo>, key_event: &KeyEvent, event: &Event) -> bool {
+        let key = key_event.key;
+
+        if key_event.value() == PRESS {
+            if let Some(delay) = find_delay(&self.bounce_keys, device, |bounce| (&bounce.device, bounce.delay)) {
+                if self
+                    .released_at
+                    .get(&key)
+                    .is_some_and(|released_at| released_at.elapsed() < delay)
+                {
+                    debug!("Bounce keys ignored press of {key:?}");
+                    self.suppressed.insert(key);
+                    return false;
+                }
+            }
+
+            if let Some(delay) = find_delay(&self.slow_keys, device, |slow| (&slow.device, slow.delay)) {
+                if let Err(err) = self.timeout_manager.set_timeout(delay) {
+                    error!("Failed to set_timeout: {err}");
+                }
+                self.pending.push((Instant::now() + delay, event.clone()));
+                return false;
+            }
+
+            return true;
+        }
+
+        let pending = self.pending.iter().position(|(_, event)| is_key(event, key));
+
+        if key_event.value() == RELEASE {
+            if self.suppressed.remove(&key) {
+                return false;
+            }
+
+            if let Some(index) = pending {
+                debug!("Slow keys ignored press of {key:?}");
+                self.pending.remove(index);
+                return false;
+            }
+
+            self.released_at.insert(key, Instant::now());
+            return true;
+        }
+
+        // Repeat
+        !self.suppressed.contains(&key) && pending.is_none()
+    }
+
+    fn accept_pending(&mut self) -> Vec<Event> {
+        let now = Instant::now();
+        let (accepted, pending) = self.pending.drain(..).partition(|(accept_at, _)| *accept_at <= now);
+        self.pending = pending;
+        accepted.into_iter().map(|(_, event)| event).collect()
+    }
+}
+
+fn find_delay<T>(
+    filters: &[T],
+    device: &InputDeviceInfo,
+    get: impl Fn(&T) -> (&Option<DeviceMatcher>, Duration),
+) -> Option<Duration> {
+    filters.iter().map(get).find_map(|(matcher, delay)| match matcher {
+        Some(matcher) if !matcher.matches(device) => None,
+        _ => Some(delay),
+    })
+}
+
+fn is_key(event: &Event, key: Key) -> bool {
+    matches!(event, Event::KeyEvent(_, key_event) if key_event.key == key)
+}
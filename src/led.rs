@@ -0,0 +1,202 @@
+use crate::config::led::LedIndicator;
+use crate::config::Config;
+use crate::device::extra_device_name;
+use evdev::{BusType, EventType, InputEvent, KeyCode as Key, LedCode};
+use nix::libc;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd};
+use std::os::unix::fs::OpenOptionsExt;
+
+// The uinput ioctls that evdev doesn't expose for LEDs, see linux/uinput.h
+#[cfg(target_os = "linux")]
+nix::ioctl_none!(ui_dev_create, b'U', 1);
+#[cfg(target_os = "linux")]
+nix::ioctl_write_ptr!(ui_dev_setup, b'U', 3, libc::uinput_setup);
+#[cfg(target_os = "linux")]
+nix::ioctl_write_int!(ui_set_evbit, b'U', 100);
+#[cfg(target_os = "linux")]
+nix::ioctl_write_int!(ui_set_keybit, b'U', 101);
+#[cfg(target_os = "linux")]
+nix::ioctl_write_int!(ui_set_ledbit, b'U', 105);
+
+const LEDS: [LedCode; 5] = [
+    LedCode::LED_NUML,
+    LedCode::LED_CAPSL,
+    LedCode::LED_SCROLLL,
+    LedCode::LED_COMPOSE,
+    LedCode::LED_KANA,
+];
+
+// A keyboard that only receives the LEDs, which the compositor or the console sets on all keyboards.
+// The LEDs can't be received on the output device, because evdev 0.13's VirtualDeviceBuilder has no
+// way to enable LEDs and keeps its uinput fd private. So this device is set up with uinput directly.
+pub struct LedReceiver {
+    file: File,
+}
+
+impl LedReceiver {
+    #[cfg(target_os = "linux")]
+    pub fn new(own_device: &str) -> io::Result<LedReceiver> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open("/dev/uinput")?;
+        let fd = file.as_raw_fd();
+
+        let mut setup = libc::uinput_setup {
+            id: libc::input_id {
+                bustype: BusType::BUS_VIRTUAL.0,
+                vendor: 0x1234,
+                product: 0x5678,
+                version: 0x111,
+            },
+            name: [0; libc::UINPUT_MAX_NAME_SIZE],
+            ff_effects_max: 0,
+        };
+        // The name is shorter than UINPUT_MAX_NAME_SIZE, so it stays null-terminated.
+        for (target, byte) in setup.name.iter_mut().zip(extra_device_name(own_device, "leds").bytes()) {
+            *target = byte as libc::c_char;
+        }
+
+        // SAFETY: `fd` is an open uinput device, the bits are plain integers, and `setup` outlives the call.
+        unsafe {
+            ui_set_evbit(fd, EventType::KEY.0 as _)?;
+            // Enough keys to be treated as a keyboard
+            for code in Key::KEY_ESC.code()..=Key::KEY_SCROLLLOCK.code() {
+                ui_set_keybit(fd, code as _)?;
+            }
+            ui_set_evbit(fd, EventType::LED.0 as _)?;
+            for led in LEDS {
+                ui_set_ledbit(fd, led.0 as _)?;
+            }
+            ui_dev_setup(fd, &setup)?;
+            ui_dev_create(fd)?;
+        }
+        Ok(LedReceiver { file })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn new(_own_device: &str) -> io::Result<LedReceiver> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "LEDs can only be synced on Linux"))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_events(&mut self) -> io::Result<Vec<(LedCode, bool)>> {
+        let mut leds = vec![];
+        let mut buffer = [0u8; size_of::<libc::input_event>()];
+        loop {
+            match self.file.read(&mut buffer) {
+                Ok(size) if size == buffer.len() => {
+                    // SAFETY: the kernel wrote a whole input_event, and any bytes are a valid input_event.
+                    let event: libc::input_event = unsafe { std::ptr::read_unaligned(buffer.as_ptr().cast()) };
+                    if event.type_ == EventType::LED.0 {
+                        leds.push((LedCode(event.code), event.value != 0));
+                    }
+                }
+                Ok(_) => return Ok(leds),
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(leds),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read_events(&mut self) -> io::Result<Vec<(LedCode, bool)>> {
+        Ok(vec![])
+    }
+}
+
+impl AsFd for LedReceiver {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.file.as_fd()
+    }
+}
+
+// The LEDs to set on the grabbed keyboards
+pub struct Leds {
+    // With sync_leds
+    receiver: Option<LedReceiver>,
+    // LEDs set by the compositor or the console
+    synced: HashMap<LedCode, bool>,
+    // LEDs of led_indicators. Lit indicators take precedence over synced LEDs.
+    indicators: HashMap<LedCode, bool>,
+    // LEDs last set on the keyboards
+    sent: HashMap<LedCode, bool>,
+}
+
+impl Leds {
+    pub fn new(config: &Config, own_device: &str) -> Leds {
+        let receiver = if config.sync_leds {
+            LedReceiver::new(own_device)
+                .inspect_err(|error| eprintln!("warning: Failed to create a device for syncing LEDs: {error}"))
+                .ok()
+        } else {
+            None
+        };
+        Leds {
+            receiver,
+            synced: HashMap::new(),
+            indicators: HashMap::new(),
+            sent: HashMap::new(),
+        }
+    }
+
+    pub fn receiver(&self) -> Option<&LedReceiver> {
+        self.receiver.as_ref()
+    }
+
+    // Returns the LEDs set by the compositor or the console, which follow the state of the locks
+    pub fn receive(&mut self) -> io::Result<Vec<(LedCode, bool)>> {
+        let Some(receiver) = &mut self.receiver else {
+            return Ok(vec![]);
+        };
+        let leds = receiver.read_events()?;
+        self.synced.extend(leds.iter().copied());
+        Ok(leds)
+    }
+
+    pub fn update_indicators(&mut self, indicators: &[LedIndicator], mode: &str, mark_set: bool) {
+        self.indicators = indicator_state(indicators, mode, mark_set);
+    }
+
+    // All LEDs, for newly grabbed keyboards
+    pub fn events(&self) -> Vec<InputEvent> {
+        self.state()
+            .into_iter()
+            .map(|(led, lit)| InputEvent::new(EventType::LED.0, led.0, lit as i32))
+            .collect()
+    }
+
+    // The LEDs that changed since the last call
+    pub fn take_changes(&mut self) -> Vec<InputEvent> {
+        let mut events = vec![];
+        for (led, lit) in self.state() {
+            if self.sent.insert(led, lit) != Some(lit) {
+                events.push(InputEvent::new(EventType::LED.0, led.0, lit as i32));
+            }
+        }
+        events
+    }
+
+    fn state(&self) -> HashMap<LedCode, bool> {
+        let mut state = self.synced.clone();
+        for (led, lit) in &self.indicators {
+            let synced = state.entry(*led).or_default();
+            *synced = *synced || *lit;
+        }
+        state
+    }
+}
+
+// Whether the LED of each indicator is lit
+pub fn indicator_state(indicators: &[LedIndicator], mode: &str, mark_set: bool) -> HashMap<LedCode, bool> {
+    let mut state: HashMap<LedCode, bool> = HashMap::new();
+    for indicator in indicators {
+        let lit = state.entry(indicator.led).or_default();
+        *lit = *lit || indicator.is_lit(mode, mark_set);
+    }
+    state
+}
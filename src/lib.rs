@@ -25,50 +25,97 @@ mod bridge;
 mod client;
 mod command_runner;
 mod config;
+mod debounce;
 mod device;
+mod emergency_escape;
 mod emit_handler;
 mod event;
 mod event_handler;
+mod gamepad;
+mod gesture;
+mod input_filter;
+mod led;
 mod main_controller;
 mod main_impl;
+mod mouse_keys;
+mod operator_auto_shift;
 mod operator_double_tap;
 mod operator_handler;
+mod operator_sequence;
 mod operator_sim;
+mod operator_tap_dance;
 mod operators;
 mod plugin;
+mod pointer;
 #[cfg(test)]
 mod tests;
 #[cfg(test)]
 mod tests_any_key;
 #[cfg(test)]
+mod tests_caps_word;
+#[cfg(test)]
+mod tests_debounce;
+#[cfg(test)]
 mod tests_disguised_events_in;
 #[cfg(test)]
+mod tests_emergency_escape;
+#[cfg(test)]
 mod tests_escape_next_key;
 #[cfg(test)]
 mod tests_extra_modifiers;
 #[cfg(test)]
+mod tests_gamepad;
+#[cfg(test)]
+mod tests_gesture;
+#[cfg(test)]
+mod tests_input_filter;
+#[cfg(test)]
 mod tests_keymap_mark;
 #[cfg(test)]
 mod tests_keymap_mode;
 #[cfg(test)]
 mod tests_keymap_modifier_triggers;
 #[cfg(test)]
+mod tests_led;
+#[cfg(test)]
+mod tests_lock;
+#[cfg(test)]
+mod tests_mirror;
+#[cfg(test)]
 mod tests_modmap_keys;
 #[cfg(test)]
 mod tests_modmap_mul_purpose;
 #[cfg(test)]
+mod tests_modmap_mul_purpose_home_row;
+#[cfg(test)]
 mod tests_modmap_mul_purpose_tap_preferred;
 #[cfg(test)]
+mod tests_modmap_one_shot;
+#[cfg(test)]
 mod tests_modmap_press_release_key;
 #[cfg(test)]
+mod tests_mouse_keys;
+#[cfg(test)]
 mod tests_nested_remap;
 #[cfg(test)]
+mod tests_operator_auto_shift;
+#[cfg(test)]
 mod tests_operator_double_tap;
 #[cfg(test)]
 mod tests_operator_handler;
 #[cfg(test)]
+mod tests_operator_sequence;
+#[cfg(test)]
 mod tests_operator_sim;
 #[cfg(test)]
+mod tests_operator_tap_dance;
+#[cfg(test)]
+mod tests_output_routing;
+#[cfg(test)]
+mod tests_pointer;
+#[cfg(test)]
+mod tests_scan_code;
+#[cfg(test)]
 mod tests_throttle_emit;
 #[cfg(test)]
 mod tests_virtual_modifier;
@@ -6,12 +6,15 @@ use crate::platform_linux::{ConfigWatcher, DeviceWatcher};
 use crate::action_dispatcher::ActionDispatcher;
 use crate::client::print_open_windows;
 use crate::config::{load_configs, Config};
+use crate::debounce::Debouncer;
 use crate::device::{
-    choose_device_name, open_device, output_device, print_device_details, print_device_list, select_input_devices,
-    InputDevice, InputDeviceInfo,
+    choose_device_name, gamepad_device, input_device_output, keyboard_device, mouse_device, open_device, output_device,
+    print_device_details, print_device_list, select_input_devices, InputDevice, InputDeviceInfo,
 };
 use crate::event::Event;
 use crate::event_handler::EventHandler;
+use crate::input_filter::InputFilter;
+use crate::led::{LedReceiver, Leds};
 use crate::main_controller::MainController;
 use crate::operator_handler::OperatorHandler;
 use crate::plugin::{apply_plugin, Plugin};
@@ -20,15 +23,21 @@ use crate::timeout_manager::TimeoutManager;
 use anyhow::{anyhow, bail, Context};
 use clap::{CommandFactory, Parser, ValueEnum};
 use clap_complete::Shell;
+use evdev::InputEvent;
+use nix::errno::Errno;
 use nix::libc::ENODEV;
 use nix::sys::select::{select, FdSet};
+use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
 use nix::sys::timerfd::{ClockId, TimerFd, TimerFlags};
 use std::collections::HashMap;
-use std::io::stdout;
+use std::io::{self, stdout, PipeReader, PipeWriter};
 use std::os::fd::{AsFd, RawFd};
 use std::os::unix::io::AsRawFd;
+use std::panic::{self, PanicHookInfo};
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, TryLockError};
 use std::time::Duration;
 
 #[derive(Parser, Debug)]
@@ -41,9 +50,13 @@ struct Args {
     #[arg(long, value_delimiter = ',')]
     ignore: Vec<String>,
     /// Listen to mouse devices. Default is false.
-    /// Trackpads, tablets and other absolute devices are not supported.
+    /// Touchpads get a mirror device for their absolute axes.
+    /// Tablets, touchscreens and other absolute devices are not supported.
     #[arg(long, verbatim_doc_comment)]
     mouse: bool,
+    /// Listen to gamepads and joysticks. Default is false.
+    #[arg(long)]
+    gamepad: bool,
     /// Watch for new devices or changing configuration files.
     /// Default is not watching for either.
     /// Examples
@@ -120,6 +133,7 @@ enum WatchTargets {
 #[derive(Debug)]
 pub enum MainAction {
     Exit,
+    EmergencyExit,
     Reload { full: bool },
     RemoveDevice(Rc<InputDeviceInfo>),
 }
@@ -138,6 +152,7 @@ pub fn xremap_cli(mut plugin: impl Plugin) -> anyhow::Result<()> {
         device: device_filter,
         ignore: ignore_filter,
         mouse,
+        gamepad,
         watch,
         configs: config_paths,
         completions,
@@ -199,11 +214,14 @@ pub fn xremap_cli(mut plugin: impl Plugin) -> anyhow::Result<()> {
         ),
     };
 
+    handle_termination_signals()?;
+    let default_panic_hook: Arc<PanicHook> = panic::take_hook().into();
+
     'main_loop: loop {
         let timeout_manager = Rc::new(TimeoutManager::new());
 
         let mut input_devices =
-            select_input_devices(&device_filter, &ignore_filter, mouse, watch_devices, &own_device)?;
+            select_input_devices(&device_filter, &ignore_filter, mouse, gamepad, watch_devices, &own_device)?;
 
         // Watchers
         let device_watcher = DeviceWatcher::new(watch_devices).context("Setting up device watcher")?;
@@ -214,8 +232,18 @@ pub fn xremap_cli(mut plugin: impl Plugin) -> anyhow::Result<()> {
         let mut mainctrl = MainController::new(!no_window_logging, allow_launch.unwrap_or(true));
 
         // OperatorHandler
-        let operator_handler = if config.experimental_map.len() > 0 {
-            Some(OperatorHandler::new(&config.experimental_map, timeout_manager.clone()))
+        let operator_handler = if config.experimental_map.len() > 0 || config.auto_shift.is_some() {
+            Some(OperatorHandler::new(&config.experimental_map, &config.auto_shift, timeout_manager.clone()))
+        } else {
+            None
+        };
+
+        // Debouncer
+        let mut debouncer = Debouncer::new(&config.debounce);
+
+        // InputFilter
+        let input_filter = if !config.slow_keys.is_empty() || !config.bounce_keys.is_empty() {
+            Some(InputFilter::new(&config.slow_keys, &config.bounce_keys, timeout_manager.clone()))
         } else {
             None
         };
@@ -223,16 +251,55 @@ pub fn xremap_cli(mut plugin: impl Plugin) -> anyhow::Result<()> {
         // EventHandler
         let timer = TimerFd::new(ClockId::CLOCK_MONOTONIC, TimerFlags::empty())?;
         let delay = Duration::from_millis(config.keypress_delay_ms);
-        let mut handler = EventHandler::new(timer, &config.default_mode, delay, operator_handler);
+        let mut handler = EventHandler::new(
+            timer,
+            timeout_manager.clone(),
+            &config.default_mode,
+            delay,
+            operator_handler,
+            input_filter,
+        );
+        for led in input_devices.values().flat_map(InputDevice::leds_on) {
+            handler.set_lock(led, true);
+        }
 
-        let output_device = output_device(
-            input_devices.values().next().map(InputDevice::bus_type),
-            config.enable_wheel,
-            vendor,
-            product,
-            &own_device,
-        )
-        .context("Failed to prepare an output device")?;
+        let bus_type = input_devices.values().next().map(InputDevice::bus_type);
+        let (output_device, mouse_device) = match &config.split_output {
+            Some(split_output) => {
+                let keyboard = &split_output.keyboard;
+                let keyboard_device = keyboard_device(
+                    bus_type,
+                    keyboard.vendor.unwrap_or(vendor),
+                    keyboard.product.unwrap_or(product),
+                    &own_device,
+                    keyboard.name.as_deref().unwrap_or("keyboard"),
+                )
+                .context("Failed to prepare a keyboard output device")?;
+                let mouse = &split_output.mouse;
+                let mouse_device = mouse_device(
+                    bus_type,
+                    config.enable_wheel,
+                    config.enable_gamepad,
+                    mouse.vendor.unwrap_or(vendor),
+                    mouse.product.unwrap_or(product),
+                    &own_device,
+                    mouse.name.as_deref().unwrap_or("mouse"),
+                )
+                .context("Failed to prepare a mouse output device")?;
+                (keyboard_device, Some(mouse_device))
+            }
+            None => (
+                output_device(bus_type, config.enable_wheel, vendor, product, &own_device)
+                    .context("Failed to prepare an output device")?,
+                None,
+            ),
+        };
+
+        let gamepad_device = if config.enable_gamepad {
+            Some(gamepad_device(vendor, product, &own_device).context("Failed to prepare a gamepad device")?)
+        } else {
+            None
+        };
 
         let throttle_emit = if config.throttle_ms == 0 {
             None
@@ -240,7 +307,11 @@ pub fn xremap_cli(mut plugin: impl Plugin) -> anyhow::Result<()> {
             Some(ThrottleEmit::new(Duration::from_millis(config.throttle_ms)))
         };
 
-        let mut dispatcher = ActionDispatcher::new(output_device, throttle_emit);
+        let mut dispatcher = ActionDispatcher::new(output_device, mouse_device, gamepad_device, throttle_emit);
+        release_keys_on_panic(&dispatcher, default_panic_hook.clone());
+        update_device_outputs(&input_devices, &mut dispatcher, &config, &own_device);
+
+        let mut leds = Leds::new(&config, &own_device);
 
         if config.notifications {
             mainctrl.show_popup("Ready", None);
@@ -252,13 +323,16 @@ pub fn xremap_cli(mut plugin: impl Plugin) -> anyhow::Result<()> {
                 &device_watcher,
                 &mut config_watcher,
                 &timeout_manager,
+                &mut debouncer,
                 &mut handler,
                 &mut dispatcher,
+                &mut leds,
                 &config,
                 &mut mainctrl,
                 &device_filter,
                 &ignore_filter,
                 mouse,
+                gamepad,
                 &own_device,
                 &mut plugin,
             )?;
@@ -267,8 +341,19 @@ pub fn xremap_cli(mut plugin: impl Plugin) -> anyhow::Result<()> {
                 MainAction::Exit => {
                     return Ok(());
                 }
+                MainAction::EmergencyExit => {
+                    println!("Emergency escape: ungrabbing all devices and exiting");
+                    dispatcher.release_pressed_keys(None);
+                    for input_device in input_devices.values_mut() {
+                        input_device.ungrab();
+                    }
+                    return Ok(());
+                }
                 MainAction::Reload { full } => match load_configs(&config_paths) {
                     Ok(new_config) => {
+                        // Keys might be released differently with the new config.
+                        dispatcher.release_pressed_keys(None);
+                        handler.release_held_keys();
                         config = new_config;
                         if config.notifications {
                             mainctrl.show_popup("Ready", None);
@@ -291,7 +376,9 @@ pub fn xremap_cli(mut plugin: impl Plugin) -> anyhow::Result<()> {
                 },
                 MainAction::RemoveDevice(device_info) => {
                     println!("Found a removed device: {:?}", device_info.name);
+                    dispatcher.release_pressed_keys(Some(&device_info.path));
                     input_devices.retain(|path, _| device_info.path != *path);
+                    update_device_outputs(&input_devices, &mut dispatcher, &config, &own_device);
 
                     if input_devices.is_empty() {
                         if watch_devices {
@@ -312,19 +399,53 @@ fn event_loop(
     device_watcher: &Option<DeviceWatcher>,
     config_watcher: &mut Option<ConfigWatcher>,
     timeout_manager: &Rc<TimeoutManager>,
+    debouncer: &mut Debouncer,
     handler: &mut EventHandler,
     dispatcher: &mut ActionDispatcher,
+    leds: &mut Leds,
     config: &Config,
     mainctrl: &mut MainController,
     device_filter: &[String],
     ignore_filter: &[String],
     mouse: bool,
+    gamepad: bool,
     own_device: &str,
     plugin: &mut impl Plugin,
 ) -> anyhow::Result<MainAction> {
     'event_loop: loop {
-        let readable_fds =
-            select_readable(input_devices.values(), &device_watcher, &config_watcher, &handler, &timeout_manager)?;
+        if TERMINATING.load(Ordering::Relaxed) {
+            return Ok(MainAction::Exit);
+        }
+
+        leds.update_indicators(&config.led_indicators, handler.mode(), handler.mark_set());
+        let led_events = leds.take_changes();
+        if !led_events.is_empty() {
+            for input_device in input_devices.values_mut() {
+                input_device.set_leds(&led_events);
+            }
+        }
+
+        let readable_fds = select_readable(
+            input_devices.values(),
+            &device_watcher,
+            &config_watcher,
+            leds.receiver(),
+            &handler,
+            &timeout_manager,
+        )?;
+
+        if let Some(receiver) = leds.receiver() {
+            if readable_fds.contains(&receiver.as_fd().as_raw_fd()) {
+                match leds.receive() {
+                    Ok(synced) => {
+                        for (led, on) in synced {
+                            handler.set_lock(led, on);
+                        }
+                    }
+                    Err(error) => println!("Error on reading LEDs: {error}"),
+                }
+            }
+        }
 
         if readable_fds.contains(&handler.as_fd().as_raw_fd()) {
             match handle_events(handler, dispatcher, &config, vec![Event::OverrideTimeout], mainctrl, plugin) {
@@ -336,6 +457,12 @@ fn event_loop(
 
         if readable_fds.contains(&timeout_manager.as_fd().as_raw_fd()) {
             if timeout_manager.need_timeout()? {
+                if input_devices
+                    .values()
+                    .any(|input_device| input_device.is_emergency_escape_held(config))
+                {
+                    return Ok(MainAction::EmergencyExit);
+                }
                 match handle_events(handler, dispatcher, config, vec![Event::Tick], mainctrl, plugin) {
                     Ok(None) => {}
                     Ok(Some(main_action)) => return Ok(main_action),
@@ -349,16 +476,34 @@ fn event_loop(
                 continue;
             }
 
-            if let Some(main_action) =
-                handle_input_events(input_device, handler, dispatcher, &config, mainctrl, plugin)?
-            {
+            let main_action =
+                handle_input_events(input_device, debouncer, handler, dispatcher, &config, mainctrl, plugin)?;
+            if let Some(hold) = input_device.emergency_escape_timeout(config) {
+                if let Err(error) = timeout_manager.set_timeout(hold) {
+                    println!("Error on setting the emergency_escape timeout: {error}");
+                }
+            }
+            if let Some(main_action) = main_action {
                 return Ok(main_action);
             }
         }
 
         if let Some(device_watcher) = &device_watcher {
             if let Ok(events) = device_watcher.read_events() {
-                handle_device_changes(events, input_devices, &device_filter, &ignore_filter, mouse, &own_device);
+                handle_device_changes(
+                    events,
+                    input_devices,
+                    &device_filter,
+                    &ignore_filter,
+                    mouse,
+                    gamepad,
+                    &own_device,
+                );
+                update_device_outputs(input_devices, dispatcher, config, own_device);
+                let led_events = leds.events();
+                for input_device in input_devices.values_mut() {
+                    input_device.set_leds(&led_events);
+                }
             }
         }
 
@@ -372,19 +517,72 @@ fn event_loop(
     }
 }
 
+// Set by SIGTERM and SIGINT, so that the event loop exits and the pressed keys are released.
+static TERMINATING: AtomicBool = AtomicBool::new(false);
+// Written on SIGTERM and SIGINT, so that select() wakes up even if the signal came just before it.
+static TERMINATION_PIPE: OnceLock<(PipeReader, PipeWriter)> = OnceLock::new();
+
+extern "C" fn on_termination_signal(_: nix::libc::c_int) {
+    if TERMINATING.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    if let Some((_, writer)) = TERMINATION_PIPE.get() {
+        // SAFETY: write(2) is async-signal-safe, and it's written once, so it can't block on a full pipe.
+        unsafe { nix::libc::write(writer.as_raw_fd(), [0u8].as_ptr().cast(), 1) };
+    }
+}
+
+fn handle_termination_signals() -> anyhow::Result<()> {
+    let pipe = io::pipe().context("Failed to create a pipe for termination signals")?;
+    if TERMINATION_PIPE.set(pipe).is_err() {
+        bail!("Termination signals are already handled");
+    }
+    let action = SigAction::new(SigHandler::Handler(on_termination_signal), SaFlags::empty(), SigSet::empty());
+    for signal in [Signal::SIGTERM, Signal::SIGINT] {
+        // SAFETY: The handler only sets an atomic and writes to a pipe.
+        unsafe { sigaction(signal, &action) }.context("Failed to handle termination signals")?;
+    }
+    Ok(())
+}
+
+type PanicHook = dyn Fn(&PanicHookInfo<'_>) + Send + Sync;
+
+// Drop doesn't run with panic = "abort", or when another thread panics, so the hook releases the keys too.
+fn release_keys_on_panic(dispatcher: &ActionDispatcher, default_hook: Arc<PanicHook>) {
+    let outputs = dispatcher.outputs();
+    panic::set_hook(Box::new(move |info| {
+        if let Some(outputs) = outputs.upgrade() {
+            // The lock is held, if the panic happened while emitting. Then it's left to Drop.
+            match outputs.try_lock() {
+                Ok(mut outputs) => outputs.release_pressed_keys(None),
+                Err(TryLockError::Poisoned(outputs)) => outputs.into_inner().release_pressed_keys(None),
+                Err(TryLockError::WouldBlock) => {}
+            }
+        }
+        default_hook(info);
+    }));
+}
+
 fn select_readable<'a>(
     devices: impl Iterator<Item = &'a InputDevice>,
     device_watcher: &Option<DeviceWatcher>,
     config_watcher: &Option<ConfigWatcher>,
+    led_receiver: Option<&LedReceiver>,
     event_handler: &impl AsFd,
     timeout_manager: &Rc<TimeoutManager>,
 ) -> anyhow::Result<Vec<RawFd>> {
     let mut read_fds = FdSet::new();
+    if let Some((reader, _)) = TERMINATION_PIPE.get() {
+        read_fds.insert(reader.as_fd());
+    }
     read_fds.insert(event_handler.as_fd());
     read_fds.insert(timeout_manager.as_fd());
     for device in devices {
         read_fds.insert(device.as_fd());
     }
+    if let Some(led_receiver) = led_receiver {
+        read_fds.insert(led_receiver.as_fd());
+    }
     #[cfg(target_os = "linux")]
     if let Some(device_watcher) = device_watcher {
         read_fds.insert(device_watcher.as_fd());
@@ -394,7 +592,12 @@ fn select_readable<'a>(
         read_fds.insert(config_watcher.borrow_timer());
         read_fds.insert(config_watcher.borrow_inotify());
     }
-    select(None, &mut read_fds, None, None, None)?;
+    match select(None, &mut read_fds, None, None, None) {
+        Ok(_) => {}
+        // Interrupted by a signal, which the event loop checks.
+        Err(Errno::EINTR) => return Ok(vec![]),
+        Err(error) => return Err(error.into()),
+    }
 
     // Make the result independent of borrowed fds
     Ok(read_fds.fds(None).map(|fd| fd.as_raw_fd()).collect())
@@ -402,6 +605,7 @@ fn select_readable<'a>(
 
 fn handle_input_events(
     input_device: &mut InputDevice,
+    debouncer: &mut Debouncer,
     handler: &mut EventHandler,
     dispatcher: &mut ActionDispatcher,
     config: &Config,
@@ -409,16 +613,27 @@ fn handle_input_events(
     plugin: &mut impl Plugin,
 ) -> anyhow::Result<Option<MainAction>> {
     let info = Rc::new(input_device.to_info());
-    let events = match input_device.fetch_events() {
+    let events: Vec<InputEvent> = match input_device.fetch_events() {
         Err(err) if err.raw_os_error() == Some(ENODEV) => {
             // The device doesn't exist anymore.
             return Ok(Some(MainAction::RemoveDevice(info)));
         }
-        events => events.context("Error fetching input events")?,
+        events => events.context("Error fetching input events")?.collect(),
     };
-
-    let input_events = events.map(|e| Event::new(info.clone(), e)).collect();
-    handle_events(handler, dispatcher, config, input_events, mainctrl, plugin)
+    if input_device.is_emergency_escape(&events, config) {
+        return Ok(Some(MainAction::EmergencyExit));
+    }
+    let events = input_device.mirror_events(events, config);
+
+    let input_events = debouncer
+        .filter_events(&info, events.into_iter())
+        .into_iter()
+        .map(|e| Event::new(info.clone(), e))
+        .collect();
+    dispatcher.set_source(Some(info.path.clone()));
+    let result = handle_events(handler, dispatcher, config, input_events, mainctrl, plugin);
+    dispatcher.set_source(None);
+    result
 }
 
 // Handle an Event with EventHandler, and dispatch Actions with ActionDispatcher
@@ -454,6 +669,7 @@ fn handle_device_changes(
     device_filter: &[String],
     ignore_filter: &[String],
     mouse: bool,
+    gamepad: bool,
     own_device: &str,
 ) {
     // Ignore already grabbed devices.
@@ -468,7 +684,9 @@ fn handle_device_changes(
         }
         ignore.push(path.clone());
         let mut device = open_device(path)?;
-        if device.is_input_device(device_filter, ignore_filter, mouse, own_device) && device.grab() {
+        if device.is_input_device(device_filter, ignore_filter, mouse, gamepad, own_device)
+            && device.grab(mouse, gamepad, own_device)
+        {
             device.print();
             Some(device.into())
         } else {
@@ -476,3 +694,26 @@ fn handle_device_changes(
         }
     }));
 }
+
+// Create and remove the output devices of `output_per_device`, as input devices come and go.
+fn update_device_outputs(
+    input_devices: &HashMap<PathBuf, InputDevice>,
+    dispatcher: &mut ActionDispatcher,
+    config: &Config,
+    own_device: &str,
+) {
+    if !config.output_per_device {
+        return;
+    }
+    dispatcher.retain_outputs(|path| input_devices.contains_key(path));
+    for (path, input_device) in input_devices {
+        if dispatcher.has_output(path) {
+            continue;
+        }
+        let capabilities = input_device.capabilities(config.enable_wheel);
+        match input_device_output(input_device, &capabilities, own_device) {
+            Ok(output) => dispatcher.add_output(path.clone(), output, capabilities),
+            Err(error) => eprintln!("warning: Failed to create output for '{}': {error}", input_device.device_name()),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::config::mouse_keys::{MouseKeys, MouseMove, Scroll};
+use crate::event::RelativeEvent;
+use evdev::{KeyCode as Key, RelativeAxisCode};
+use std::time::Instant;
+
+// The high resolution wheel value of one notch.
+const NOTCH: i32 = 120;
+
+// The pointer movement and scrolling of `mouse_move`, `scroll` and `drag_scroll` actions,
+// while their trigger keys are held.
+pub struct MouseKeysState {
+    // Trigger keys that are held, and the direction they move in.
+    held: Vec<(Key, MouseMove)>,
+    // Trigger keys that are held, and the direction they scroll in.
+    scrolling: Vec<(Key, Scroll)>,
+    // Trigger key of drag scroll
+    drag_scroll: Option<Key>,
+    // When the movement started, for acceleration.
+    started_at: Instant,
+    // When the pointer was last moved.
+    moved_at: Instant,
+    // Fractions of pixels that are not emitted yet.
+    remainder: (f64, f64),
+    // Fractions of high resolution wheel values that are not emitted yet, (horizontal, vertical).
+    wheel_remainder: (f64, f64),
+    // High resolution wheel values that are not a full notch yet, (horizontal, vertical).
+    wheel_notch: (i32, i32),
+}
+
+impl MouseKeysState {
+    pub fn new() -> Self {
+        MouseKeysState {
+            held: vec![],
+            scrolling: vec![],
+            drag_scroll: None,
+            started_at: Instant::now(),
+            moved_at: Instant::now(),
+            remainder: (0.0, 0.0),
+            wheel_remainder: (0.0, 0.0),
+            wheel_notch: (0, 0),
+        }
+    }
+
+    // Returns true, if the ticks are started by this key.
+    pub fn start(&mut self, key: Key, direction: &MouseMove) -> bool {
+        if self.held.iter().any(|(held, _)| *held == key) {
+            // Repeated
+            return false;
+        }
+
+        let started = !self.is_moving();
+
+        if self.held.is_empty() {
+            self.started_at = Instant::now();
+            self.remainder = (0.0, 0.0);
+        }
+        if started {
+            self.moved_at = Instant::now();
+        }
+
+        self.held.push((key, direction.clone()));
+
+        started
+    }
+
+    // Returns true, if the ticks are started by this key.
+    pub fn start_scroll(&mut self, key: Key, direction: &Scroll) -> bool {
+        if self.scrolling.iter().any(|(held, _)| *held == key) {
+            // Repeated
+            return false;
+        }
+
+        let started = !self.is_moving();
+
+        if started {
+            self.moved_at = Instant::now();
+        }
+
+        self.scrolling.push((key, direction.clone()));
+
+        started
+    }
+
+    pub fn start_drag_scroll(&mut self, key: Key) {
+        self.drag_scroll = Some(key);
+    }
+
+    pub fn stop_drag_scroll(&mut self) {
+        self.drag_scroll = None;
+    }
+
+    pub fn stop(&mut self, key: Key) {
+        self.held.retain(|(held, _)| *held != key);
+        self.scrolling.retain(|(held, _)| *held != key);
+        if self.drag_scroll == Some(key) {
+            self.drag_scroll = None;
+        }
+    }
+
+    // Whether ticks are needed.
+    pub fn is_moving(&self) -> bool {
+        !self.held.is_empty() || !self.scrolling.is_empty()
+    }
+
+    // The movement and scrolling since the last tick.
+    pub fn on_tick(&mut self, config: &MouseKeys, precise: bool) -> Vec<RelativeEvent> {
+        let seconds = self.moved_at.elapsed().as_secs_f64();
+        self.moved_at = Instant::now();
+
+        let mut events = vec![];
+
+        if !self.held.is_empty() {
+            let (x, y) = self
+                .held
+                .iter()
+                .fold((0, 0), |(x, y), (_, direction)| (x + direction.x, y + direction.y));
+
+            let pixels = config.speed(self.started_at.elapsed(), precise) * seconds;
+
+            let dx = self.remainder.0 + x as f64 * pixels;
+            let dy = self.remainder.1 + y as f64 * pixels;
+            self.remainder = (dx.fract(), dy.fract());
+
+            events.extend(relative_events(&[
+                (RelativeAxisCode::REL_X, dx.trunc() as i32),
+                (RelativeAxisCode::REL_Y, dy.trunc() as i32),
+            ]));
+        }
+
+        if !self.scrolling.is_empty() {
+            let (horizontal, vertical) =
+                self.scrolling
+                    .iter()
+                    .fold((0, 0), |(horizontal, vertical), (_, direction)| {
+                        (horizontal + direction.horizontal, vertical + direction.vertical)
+                    });
+
+            let hi_res = config.scroll_speed * NOTCH as f64 * seconds;
+
+            events.extend(self.scroll(horizontal as f64 * hi_res, vertical as f64 * hi_res));
+        }
+
+        events
+    }
+
+    // The scrolling of a mouse movement while drag scroll is held. None if not dragging.
+    pub fn drag_scroll(&mut self, event: &RelativeEvent, config: &MouseKeys) -> Option<Vec<RelativeEvent>> {
+        self.drag_scroll?;
+
+        let hi_res = event.value as f64 * NOTCH as f64 / config.drag_scroll_distance;
+
+        if event.code == RelativeAxisCode::REL_X.0 {
+            Some(self.scroll(hi_res, 0.0))
+        } else if event.code == RelativeAxisCode::REL_Y.0 {
+            // Moving up scrolls up, and the wheel is positive upwards.
+            Some(self.scroll(0.0, -hi_res))
+        } else {
+            None
+        }
+    }
+
+    // Emit the high resolution values, and a notch when that is reached.
+    fn scroll(&mut self, horizontal: f64, vertical: f64) -> Vec<RelativeEvent> {
+        let horizontal = self.wheel_remainder.0 + horizontal;
+        let vertical = self.wheel_remainder.1 + vertical;
+        self.wheel_remainder = (horizontal.fract(), vertical.fract());
+
+        let (horizontal, vertical) = (horizontal.trunc() as i32, vertical.trunc() as i32);
+
+        let horizontal_notch = self.wheel_notch.0 + horizontal;
+        let vertical_notch = self.wheel_notch.1 + vertical;
+        self.wheel_notch = (horizontal_notch % NOTCH, vertical_notch % NOTCH);
+
+        relative_events(&[
+            (RelativeAxisCode::REL_HWHEEL_HI_RES, horizontal),
+            (RelativeAxisCode::REL_WHEEL_HI_RES, vertical),
+            (RelativeAxisCode::REL_HWHEEL, horizontal_notch / NOTCH),
+            (RelativeAxisCode::REL_WHEEL, vertical_notch / NOTCH),
+        ])
+    }
+}
+
+fn relative_events(values: &[(RelativeAxisCode, i32)]) -> Vec<RelativeEvent> {
+    values
+        .iter()
+        .filter(|(_, value)| *value != 0)
+        .map(|(axis, value)| RelativeEvent::new_with(axis.0, *value))
+        .collect()
+}
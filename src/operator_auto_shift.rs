@@ -0,0 +1,145 @@
+use crate::config::auto_shift::AutoShift;
+use crate::device::InputDeviceInfo;
+use crate::emit_handler::Emit;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::event_handler::{PRESS, RELEASE, REPEAT};
+use crate::operators::{ActiveOperator, OperatorAction, StaticOperator};
+use crate::timeout_manager::TimeoutManager;
+use evdev::KeyCode as Key;
+use log::error;
+use std::mem::swap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Function
+///  - If the key is released before the timeout, then it's emitted as normal.
+///  - If the key is held beyond the timeout, then it's tapped with shift,
+///    and the rest of the key press is suppressed.
+///  - If another key is pressed before the timeout, then the key is pressed
+///    as normal, and the rest of the key press is let through.
+///  - Repeat events are suppressed while waiting.
+#[derive(Debug)]
+pub struct AutoShiftOperator {
+    timeout: Duration,
+    timeout_manager: Rc<TimeoutManager>,
+}
+
+impl AutoShiftOperator {
+    pub fn get_ops(auto_shift: &AutoShift, timeout_manager: Rc<TimeoutManager>) -> Vec<(Key, Box<dyn StaticOperator>)> {
+        auto_shift
+            .keys()
+            .into_iter()
+            .map(|key| {
+                let operator: Box<dyn StaticOperator> = Box::new(AutoShiftOperator {
+                    timeout: auto_shift.timeout,
+                    timeout_manager: timeout_manager.clone(),
+                });
+                (key, operator)
+            })
+            .collect()
+    }
+}
+
+impl StaticOperator for AutoShiftOperator {
+    fn get_active_operator(&self, event: &Event) -> Box<dyn ActiveOperator> {
+        if let Err(err) = self.timeout_manager.set_timeout(self.timeout) {
+            error!("Failed to set_timeout: {err}");
+        }
+
+        match event {
+            Event::KeyEvent(device, key_event) => Box::new(ActiveAutoShiftOperator {
+                key: key_event.key,
+                device: device.clone(),
+                timeout: self.timeout,
+                start_inst: Instant::now(),
+                buffered: vec![],
+                shifted: false,
+            }),
+            _ => {
+                unreachable!()
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ActiveAutoShiftOperator {
+    key: Key,
+    device: Rc<InputDeviceInfo>,
+    timeout: Duration,
+    start_inst: Instant,
+    buffered: Vec<Event>,
+    // The shifted key is emitted, and the trigger key is suppressed until released.
+    shifted: bool,
+}
+
+impl ActiveOperator for ActiveAutoShiftOperator {
+    fn on_event(&mut self, event: &Event) -> OperatorAction {
+        match event {
+            Event::KeyEvent(_, key_event) if key_event.key == self.key => self.on_trigger_key(key_event),
+            // Other keys aren't buffered after the shifted key is emitted.
+            _ if self.shifted => OperatorAction::Unhandled,
+            Event::KeyEvent(_, key_event) if key_event.value() == PRESS => {
+                // Another key decides the normal key.
+                self.buffered.push(event.clone());
+                OperatorAction::Done(vec![self.emit(self.key, KeyValue::Press)], self.take_buffered())
+            }
+            Event::Tick => self.on_tick(),
+            _ => {
+                self.buffered.push(event.clone());
+                OperatorAction::Undecided
+            }
+        }
+    }
+}
+
+impl ActiveAutoShiftOperator {
+    fn on_trigger_key(&mut self, key_event: &KeyEvent) -> OperatorAction {
+        if key_event.value() == RELEASE {
+            let emit = if self.shifted {
+                vec![]
+            } else {
+                vec![
+                    self.emit(self.key, KeyValue::Press),
+                    self.emit(self.key, KeyValue::Release),
+                ]
+            };
+            return OperatorAction::Done(emit, self.take_buffered());
+        }
+
+        if key_event.value() == REPEAT && self.shifted {
+            // Suppress repeat of the shifted key
+            return OperatorAction::Partial(vec![], vec![]);
+        }
+
+        // Suppress repeat and spurious press
+        OperatorAction::Undecided
+    }
+
+    fn on_tick(&mut self) -> OperatorAction {
+        if self.start_inst.elapsed() <= self.timeout {
+            return OperatorAction::Undecided;
+        }
+
+        self.shifted = true;
+
+        let emit = vec![
+            self.emit(Key::KEY_LEFTSHIFT, KeyValue::Press),
+            self.emit(self.key, KeyValue::Press),
+            self.emit(self.key, KeyValue::Release),
+            self.emit(Key::KEY_LEFTSHIFT, KeyValue::Release),
+        ];
+
+        OperatorAction::Partial(emit, self.take_buffered())
+    }
+
+    fn emit(&self, key: Key, value: KeyValue) -> Emit {
+        Emit::key_event(self.device.clone(), KeyEvent::new(key, value))
+    }
+
+    fn take_buffered(&mut self) -> Vec<Event> {
+        let mut buffered = vec![];
+        swap(&mut buffered, &mut self.buffered);
+        buffered
+    }
+}
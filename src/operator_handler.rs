@@ -1,11 +1,15 @@
 use crate::client::WMClient;
+use crate::config::auto_shift::AutoShift;
 use crate::config::expmap::Expmap;
 use crate::config::expmap_operator::ExpmapOperator;
 use crate::emit_handler::{Emit, EmitHandler};
 use crate::event::Event;
 use crate::event_handler::PRESS;
+use crate::operator_auto_shift::AutoShiftOperator;
 use crate::operator_double_tap::DoubleTapOperator;
+use crate::operator_sequence::SequenceOperator;
 use crate::operator_sim::SimOperator;
+use crate::operator_tap_dance::TapDanceOperator;
 use crate::operators::{ActiveOperator, OperatorAction, OperatorEntry, StaticOperator};
 use crate::timeout_manager::TimeoutManager;
 use evdev::KeyCode as Key;
@@ -41,7 +45,11 @@ pub struct OperatorHandler {
 /// handles the last part 'AB'. If operators were only static, then it would be more complicated
 /// because they would have to keep track of the whether 'b' should be squashed or let through.
 impl OperatorHandler {
-    pub fn new(experimental_map: &Vec<Expmap>, timeout_manager: Rc<TimeoutManager>) -> OperatorHandler {
+    pub fn new(
+        experimental_map: &Vec<Expmap>,
+        auto_shift: &Option<AutoShift>,
+        timeout_manager: Rc<TimeoutManager>,
+    ) -> OperatorHandler {
         let mut lookup_map: HashMap<Key, Vec<OperatorEntry>> = HashMap::new();
 
         for expmap in experimental_map {
@@ -51,16 +59,39 @@ impl OperatorHandler {
                 append(operators, &mut lookup_map, expmap);
             }
 
+            for sequence in &expmap.sequences {
+                let operators = SequenceOperator::get_ops(sequence, timeout_manager.clone());
+
+                append(operators, &mut lookup_map, expmap);
+            }
+
             for (key, op) in &expmap.remap {
                 let operators = match op {
                     ExpmapOperator::DoubleTap(dbltap) => {
                         DoubleTapOperator::get_ops(*key, dbltap, timeout_manager.clone())
                     }
+                    ExpmapOperator::TapDance(tap_dance) => {
+                        TapDanceOperator::get_ops(*key, tap_dance, timeout_manager.clone())
+                    }
                 };
                 append(operators, &mut lookup_map, expmap);
             }
         }
 
+        if let Some(auto_shift) = auto_shift {
+            for (key, operator) in AutoShiftOperator::get_ops(auto_shift, timeout_manager.clone()) {
+                let entry = OperatorEntry {
+                    operator,
+                    application: auto_shift.application.clone(),
+                    title: auto_shift.window.clone(),
+                    device: None,
+                    mode: None,
+                    unmodified: true,
+                };
+                append_entry(key, entry, &mut lookup_map);
+            }
+        }
+
         OperatorHandler {
             active: vec![],
             candidates: None,
@@ -83,16 +114,24 @@ impl OperatorHandler {
     #[cfg(test)]
     pub fn map_evs(&mut self, events: Vec<Event>) -> Vec<Event> {
         let mut wmclient = WMClient::new("none", Box::new(crate::client::null_client::NullClient), false);
-        self.map_events(events, &mut wmclient)
+        self.map_events(events, &mut wmclient, "default")
     }
 
-    pub fn map_events(&mut self, events: Vec<Event>, wmclient: &mut WMClient) -> Vec<Event> {
+    pub fn map_events(&mut self, events: Vec<Event>, wmclient: &mut WMClient, mode: &str) -> Vec<Event> {
         events
             .into_iter()
             .flat_map(|event| {
                 self.emit_handler.on_event(&event);
 
-                let events = process_event(event, &mut self.active, &mut self.candidates, &self.lookup_map, wmclient);
+                let events = process_event(
+                    event,
+                    &mut self.active,
+                    &mut self.candidates,
+                    &self.lookup_map,
+                    wmclient,
+                    mode,
+                    self.emit_handler.physical_modifiers(),
+                );
 
                 self.emit_handler.map_output(events)
             })
@@ -110,18 +149,25 @@ fn append(
             operator,
             application: expmap.application.clone(),
             title: expmap.window.clone(),
+            device: expmap.device.clone(),
+            mode: expmap.mode.clone(),
+            unmodified: false,
         };
-        match lookup_map.get_mut(&key) {
-            Some(current) => {
-                current.push(entry);
-            }
-            None => {
-                lookup_map.insert(key, vec![entry]);
-            }
-        };
+        append_entry(key, entry, lookup_map);
     }
 }
 
+fn append_entry(key: Key, entry: OperatorEntry, lookup_map: &mut HashMap<Key, Vec<OperatorEntry>>) {
+    match lookup_map.get_mut(&key) {
+        Some(current) => {
+            current.push(entry);
+        }
+        None => {
+            lookup_map.insert(key, vec![entry]);
+        }
+    };
+}
+
 #[derive(Debug)]
 enum CandidateState {
     Canceled,
@@ -164,6 +210,8 @@ fn process_event(
     candidates: &mut Option<Candidates>,
     lookup_map: &HashMap<Key, Vec<OperatorEntry>>,
     wmclient: &mut WMClient,
+    mode: &str,
+    modifiers: &[Key],
 ) -> Vec<Emit> {
     // The events that have passed fully through the operators.
     let mut emit: Vec<Emit> = vec![];
@@ -205,7 +253,7 @@ fn process_event(
                     None => {
                         match candidates {
                             Some(candidates) => try_candidates(event, &mut left, candidates),
-                            None => static_lookup(event, candidates, &lookup_map, &mut emit, wmclient),
+                            None => static_lookup(event, candidates, &lookup_map, &mut emit, wmclient, mode, modifiers),
                         };
                     }
                 };
@@ -329,6 +377,8 @@ fn static_lookup(
     lookup_map: &HashMap<Key, Vec<OperatorEntry>>,
     emit: &mut Vec<Emit>,
     wmclient: &mut WMClient,
+    mode: &str,
+    modifiers: &[Key],
 ) {
     let (device, key_event) = match &event {
         Event::KeyEvent(device, key_event) => (device, key_event),
@@ -368,6 +418,22 @@ fn static_lookup(
                         }
                     }
 
+                    if let Some(device_matcher) = &entry.device {
+                        if !device_matcher.matches(device) {
+                            return false;
+                        }
+                    }
+
+                    if let Some(modes) = &entry.mode {
+                        if !modes.iter().any(|m| m == mode) {
+                            return false;
+                        }
+                    }
+
+                    if entry.unmodified && !modifiers.is_empty() {
+                        return false;
+                    }
+
                     true
                 })
                 .map(|entry| Candidate {
@@ -378,6 +444,12 @@ fn static_lookup(
                 })
                 .collect();
 
+            if new_candidates.is_empty() {
+                // No operator applies here, so there's nothing to wait for.
+                emit.push(Emit::key_event(device.clone(), key_event.clone()));
+                return;
+            }
+
             candidates.replace(Candidates {
                 start_event: event,
                 events: vec![],
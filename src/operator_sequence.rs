@@ -0,0 +1,294 @@
+use crate::config::expmap_operator::ExpmapAction;
+use crate::config::expmap_sequence::Sequence;
+use crate::device::InputDeviceInfo;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::event_handler::{PRESS, RELEASE, REPEAT};
+use crate::operators::{map_actions, ActiveOperator, OperatorAction, StaticOperator};
+use crate::timeout_manager::TimeoutManager;
+use evdev::KeyCode as Key;
+use log::error;
+use std::mem::swap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Function
+///  - The keys must be pressed in the given order, and each key must be pressed within
+///    the timeout of the previous key. It doesn't matter if the keys overlap or not,
+///    so rolling from one key to the next is allowed.
+///  - If the next key isn't pressed within the timeout, or another key is pressed
+///    in between, then the operator cancels and the keys are emitted as normal.
+///  - The action is pressed when the last key is pressed, and released when the
+///    last key is released. Releases of the other keys are squashed.
+///  - The device of the last key is used for all emitted actions.
+#[derive(Debug)]
+pub struct SequenceOperator {
+    keys: Vec<Key>,
+    actions: Vec<ExpmapAction>,
+    timeout: Duration,
+    timeout_manager: Rc<TimeoutManager>,
+}
+
+impl SequenceOperator {
+    pub fn get_ops(sequence: &Sequence, timeout_manager: Rc<TimeoutManager>) -> Vec<(Key, Box<dyn StaticOperator>)> {
+        if sequence.keys.len() < 2 {
+            panic!("There must be at least two keys for a sequence.");
+        }
+
+        // Only the first key can start a sequence.
+        vec![(
+            sequence.keys[0],
+            Box::new(SequenceOperator {
+                keys: sequence.keys.clone(),
+                actions: sequence.actions.clone(),
+                timeout: sequence.timeout,
+                timeout_manager,
+            }),
+        )]
+    }
+}
+
+impl StaticOperator for SequenceOperator {
+    fn get_active_operator(&self, event: &Event) -> Box<dyn ActiveOperator> {
+        if let Err(err) = self.timeout_manager.set_timeout(self.timeout) {
+            error!("Failed to set_timeout: {err}");
+        }
+
+        match event {
+            Event::KeyEvent(_, key_event) => Box::new(ActiveSequenceOperator {
+                keys: self.keys.clone(),
+                actions: self.actions.clone(),
+                timeout: self.timeout,
+                timeout_manager: self.timeout_manager.clone(),
+                last_press_inst: Instant::now(),
+                buffered: vec![],
+                state: State::Pressed {
+                    next: 1,
+                    held: vec![key_event.key],
+                },
+            }),
+            _ => {
+                unreachable!()
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    // The keys before `next` have been pressed in order.
+    Pressed {
+        next: usize,
+        held: Vec<Key>,
+    },
+    // The action has been pressed.
+    Emitted {
+        device: Rc<InputDeviceInfo>,
+        held: Vec<Key>,
+    },
+    // The action has been released, and release of the held keys will be squashed.
+    Released {
+        held: Vec<Key>,
+    },
+    // All keys have been released again.
+    // Or the operator was canceled before emit.
+    Done,
+}
+
+#[derive(Debug)]
+pub struct ActiveSequenceOperator {
+    keys: Vec<Key>,
+    actions: Vec<ExpmapAction>,
+    timeout: Duration,
+    timeout_manager: Rc<TimeoutManager>,
+    // Time of the latest press that advanced the sequence
+    last_press_inst: Instant,
+    buffered: Vec<Event>,
+    state: State,
+}
+
+impl ActiveOperator for ActiveSequenceOperator {
+    fn on_event(&mut self, event: &Event) -> OperatorAction {
+        match event {
+            Event::KeyEvent(device, key_event) => {
+                if key_event.value() == PRESS {
+                    self.on_press(device.clone(), key_event)
+                } else if key_event.value() == RELEASE {
+                    self.on_release(device.clone(), key_event)
+                } else if key_event.value() == REPEAT {
+                    self.on_repeat(device.clone(), key_event)
+                } else {
+                    OperatorAction::Unhandled
+                }
+            }
+            Event::Tick => self.on_tick(),
+            _ => self.on_other(event),
+        }
+    }
+}
+
+impl ActiveSequenceOperator {
+    fn on_press(&mut self, device: Rc<InputDeviceInfo>, key_event: &KeyEvent) -> OperatorAction {
+        match &mut self.state {
+            State::Pressed { next, held } if self.keys[*next] == key_event.key => {
+                held.push(key_event.key);
+                *next += 1;
+
+                if *next < self.keys.len() {
+                    // One more key pressed, but not all, yet.
+                    self.last_press_inst = Instant::now();
+                    if let Err(err) = self.timeout_manager.set_timeout(self.timeout) {
+                        error!("Failed to set_timeout: {err}");
+                    }
+
+                    return OperatorAction::Undecided;
+                }
+
+                // All keys pressed
+                let emit = map_actions(&self.actions, device.clone(), KeyValue::Press);
+
+                // Flush buffered events.
+                let mut buffered = vec![];
+                swap(&mut buffered, &mut self.buffered);
+
+                let mut held_keys = vec![];
+                swap(&mut held_keys, held);
+                self.state = State::Emitted {
+                    device,
+                    held: held_keys,
+                };
+
+                OperatorAction::Partial(emit, buffered)
+            }
+            State::Pressed { next: _, held } if held.contains(&key_event.key) => {
+                // Suppress spurious press
+                OperatorAction::Undecided
+            }
+            State::Pressed { next: _, held: _ } => {
+                // The sequence is broken by another key.
+                self.state = State::Done;
+
+                OperatorAction::Cancel
+            }
+            State::Emitted { device: _, held } | State::Released { held } if held.contains(&key_event.key) => {
+                // Suppress spurious press
+                OperatorAction::Partial(vec![], vec![])
+            }
+            State::Emitted { device: _, held: _ } | State::Released { held: _ } => OperatorAction::Unhandled,
+            State::Done => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn on_release(&mut self, device: Rc<InputDeviceInfo>, key_event: &KeyEvent) -> OperatorAction {
+        let last_key = self.keys[self.keys.len() - 1];
+
+        match &mut self.state {
+            State::Pressed { next: _, held } => {
+                if let Some(index) = held.iter().position(|&key| key == key_event.key) {
+                    held.remove(index);
+                } else {
+                    self.buffered.push(Event::KeyEvent(device, key_event.clone()));
+                }
+
+                OperatorAction::Undecided
+            }
+            State::Emitted { device, held } if held.contains(&key_event.key) => {
+                remove_first(held, key_event.key);
+
+                if key_event.key != last_key {
+                    // To squash
+                    return OperatorAction::Partial(vec![], vec![]);
+                }
+
+                let emit = map_actions(&self.actions, device.clone(), KeyValue::Release);
+
+                if held.is_empty() {
+                    self.state = State::Done;
+
+                    OperatorAction::Done(emit, vec![])
+                } else {
+                    let mut held_keys = vec![];
+                    swap(&mut held_keys, held);
+                    self.state = State::Released { held: held_keys };
+
+                    OperatorAction::Partial(emit, vec![])
+                }
+            }
+            State::Released { held } if held.contains(&key_event.key) => {
+                remove_first(held, key_event.key);
+
+                if held.is_empty() {
+                    // All released
+                    self.state = State::Done;
+
+                    OperatorAction::Done(vec![], vec![])
+                } else {
+                    // To squash
+                    OperatorAction::Partial(vec![], vec![])
+                }
+            }
+            // Unrelated
+            State::Emitted { device: _, held: _ } | State::Released { held: _ } => OperatorAction::Unhandled,
+            State::Done => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn on_repeat(&mut self, _: Rc<InputDeviceInfo>, key_event: &KeyEvent) -> OperatorAction {
+        let last_key = self.keys[self.keys.len() - 1];
+
+        match &self.state {
+            // Suppress repeat when matching
+            State::Pressed { next: _, held: _ } => OperatorAction::Undecided,
+            State::Emitted { device, held: _ } if key_event.key == last_key => {
+                OperatorAction::Partial(map_actions(&self.actions, device.clone(), KeyValue::Repeat), vec![])
+            }
+            State::Emitted { device: _, held } | State::Released { held } if held.contains(&key_event.key) => {
+                // These are unneeded
+                OperatorAction::Partial(vec![], vec![])
+            }
+            State::Emitted { device: _, held: _ } | State::Released { held: _ } => OperatorAction::Unhandled,
+            State::Done => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn on_tick(&mut self) -> OperatorAction {
+        match &self.state {
+            State::Pressed { next: _, held: _ } if self.last_press_inst.elapsed() <= self.timeout => {
+                OperatorAction::Undecided
+            }
+            State::Pressed { next: _, held: _ } => {
+                self.state = State::Done;
+
+                OperatorAction::Cancel
+            }
+            State::Emitted { device: _, held: _ } | State::Released { held: _ } => OperatorAction::Unhandled,
+            State::Done => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn on_other(&mut self, event: &Event) -> OperatorAction {
+        match &self.state {
+            State::Pressed { next: _, held: _ } => {
+                self.buffered.push(event.clone());
+                OperatorAction::Undecided
+            }
+            State::Emitted { device: _, held: _ } | State::Released { held: _ } => OperatorAction::Unhandled,
+            State::Done => {
+                unreachable!()
+            }
+        }
+    }
+}
+
+fn remove_first(keys: &mut Vec<Key>, key: Key) {
+    if let Some(index) = keys.iter().position(|&k| k == key) {
+        keys.remove(index);
+    }
+}
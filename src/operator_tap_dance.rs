@@ -0,0 +1,321 @@
+use crate::config::expmap_operator::{ExpmapAction, TapDance};
+use crate::device::InputDeviceInfo;
+use crate::emit_handler::Emit;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::event_handler::{PRESS, RELEASE, REPEAT};
+use crate::operators::{map_actions, ActiveOperator, OperatorAction, StaticOperator};
+use crate::timeout_manager::TimeoutManager;
+use evdev::KeyCode as Key;
+use log::error;
+use std::mem::swap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Function
+///  - The key is tapped a number of times, and the tap action for that count is emitted
+///    when the timeout has passed after the last release.
+///  - If the key is held down beyond the timeout, then the hold action for that count is
+///    pressed until the key is released. If there is no hold action for that count, then
+///    the tap action is held instead.
+///  - The decision is made right away, when the last tap action is reached, or when
+///    another key is pressed after a release of the trigger key.
+///  - The device of the last press of the trigger key is used for all emitted actions.
+#[derive(Debug)]
+pub struct TapDanceOperator {
+    taps: Vec<Vec<ExpmapAction>>,
+    hold: Vec<Vec<ExpmapAction>>,
+    timeout: Duration,
+    timeout_manager: Rc<TimeoutManager>,
+}
+
+impl TapDanceOperator {
+    pub fn get_ops(
+        key: Key,
+        tap_dance: &TapDance,
+        timeout_manager: Rc<TimeoutManager>,
+    ) -> Vec<(Key, Box<dyn StaticOperator>)> {
+        vec![(
+            key,
+            Box::new(TapDanceOperator {
+                taps: tap_dance.taps.clone(),
+                hold: tap_dance.hold.clone(),
+                timeout: tap_dance.timeout,
+                timeout_manager,
+            }),
+        )]
+    }
+}
+
+impl StaticOperator for TapDanceOperator {
+    fn get_active_operator(&self, event: &Event) -> Box<dyn ActiveOperator> {
+        if let Err(err) = self.timeout_manager.set_timeout(self.timeout) {
+            error!("Failed to set_timeout: {err}");
+        }
+
+        match event {
+            Event::KeyEvent(device, key_event) => {
+                let mut operator = ActiveTapDanceOperator {
+                    key: key_event.key,
+                    taps: self.taps.clone(),
+                    hold: self.hold.clone(),
+                    timeout: self.timeout,
+                    timeout_manager: self.timeout_manager.clone(),
+                    last_inst: Instant::now(),
+                    buffered: vec![],
+                    state: State::Done,
+                };
+                operator.state = operator.pressed(1, device.clone());
+                Box::new(operator)
+            }
+            _ => {
+                unreachable!()
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    // The trigger key is down for the count'th time.
+    Pressed {
+        count: usize,
+        device: Rc<InputDeviceInfo>,
+    },
+    // The trigger key has been released count times.
+    Tapped {
+        count: usize,
+        device: Rc<InputDeviceInfo>,
+    },
+    // The actions are pressed until the trigger key is released.
+    Emitted {
+        device: Rc<InputDeviceInfo>,
+        actions: Vec<ExpmapAction>,
+    },
+    Done,
+}
+
+#[derive(Debug)]
+pub struct ActiveTapDanceOperator {
+    key: Key,
+    taps: Vec<Vec<ExpmapAction>>,
+    hold: Vec<Vec<ExpmapAction>>,
+    timeout: Duration,
+    timeout_manager: Rc<TimeoutManager>,
+    // Time of the last press or release of the trigger key
+    last_inst: Instant,
+    buffered: Vec<Event>,
+    state: State,
+}
+
+impl ActiveOperator for ActiveTapDanceOperator {
+    fn on_event(&mut self, event: &Event) -> OperatorAction {
+        match event {
+            Event::KeyEvent(device, key_event) => {
+                if key_event.value() == PRESS {
+                    self.on_press(device.clone(), key_event)
+                } else if key_event.value() == RELEASE {
+                    self.on_release(device.clone(), key_event)
+                } else if key_event.value() == REPEAT {
+                    self.on_repeat(device.clone(), key_event)
+                } else {
+                    OperatorAction::Unhandled
+                }
+            }
+            Event::Tick => self.on_tick(),
+            _ => self.on_other(event),
+        }
+    }
+}
+
+impl ActiveTapDanceOperator {
+    fn on_press(&mut self, device: Rc<InputDeviceInfo>, key_event: &KeyEvent) -> OperatorAction {
+        match &self.state {
+            State::Tapped { count, device: _ } if self.key == key_event.key => {
+                let count = *count;
+                self.state = self.pressed(count + 1, device);
+
+                match &self.state {
+                    State::Emitted { device, actions } => {
+                        let emit = map_actions(actions, device.clone(), KeyValue::Press);
+                        OperatorAction::Partial(emit, self.take_buffered())
+                    }
+                    _ => OperatorAction::Undecided,
+                }
+            }
+            State::Pressed { count: _, device: _ } if self.key == key_event.key => {
+                // Suppress spurious press
+                OperatorAction::Undecided
+            }
+            State::Pressed { count: _, device: _ } => {
+                self.buffered.push(Event::KeyEvent(device, key_event.clone()));
+                OperatorAction::Undecided
+            }
+            State::Tapped { count, device: _ } => {
+                // Another key decides the tap.
+                let count = *count;
+                self.buffered.push(Event::KeyEvent(device, key_event.clone()));
+                self.done_with_tap(count)
+            }
+            State::Emitted { device: _, actions: _ } if self.key == key_event.key => {
+                // Suppress spurious press
+                OperatorAction::Partial(vec![], vec![])
+            }
+            State::Emitted { device: _, actions: _ } => OperatorAction::Unhandled,
+            State::Done => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn on_release(&mut self, device: Rc<InputDeviceInfo>, key_event: &KeyEvent) -> OperatorAction {
+        match &self.state {
+            State::Pressed { count, device } if self.key == key_event.key => {
+                let count = *count;
+                let key_pressed_meanwhile = self.buffered.iter().any(|event| match event {
+                    Event::KeyEvent(_, key_event) => key_event.value() == PRESS,
+                    _ => false,
+                });
+
+                if count >= self.taps.len() || key_pressed_meanwhile {
+                    return self.done_with_tap(count);
+                }
+
+                self.state = State::Tapped {
+                    count,
+                    device: device.clone(),
+                };
+                self.restart_timeout();
+
+                OperatorAction::Undecided
+            }
+            State::Tapped { count: _, device: _ } if self.key == key_event.key => {
+                // Suppress spurious release
+                OperatorAction::Undecided
+            }
+            State::Pressed { count: _, device: _ } | State::Tapped { count: _, device: _ } => {
+                self.buffered.push(Event::KeyEvent(device, key_event.clone()));
+                OperatorAction::Undecided
+            }
+            State::Emitted { device, actions } if self.key == key_event.key => {
+                let emit = map_actions(actions, device.clone(), KeyValue::Release);
+                self.state = State::Done;
+
+                OperatorAction::Done(emit, vec![])
+            }
+            // Unrelated keys not buffered after emit
+            State::Emitted { device: _, actions: _ } => OperatorAction::Unhandled,
+            State::Done => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn on_repeat(&mut self, _: Rc<InputDeviceInfo>, key_event: &KeyEvent) -> OperatorAction {
+        match &self.state {
+            // Suppress repeat when matching
+            State::Pressed { count: _, device: _ } | State::Tapped { count: _, device: _ } => OperatorAction::Undecided,
+
+            // Repeat the emitted actions.
+            State::Emitted { device, actions } if self.key == key_event.key => {
+                OperatorAction::Partial(map_actions(actions, device.clone(), KeyValue::Repeat), vec![])
+            }
+
+            // Unrelated keys not buffered after emit
+            State::Emitted { device: _, actions: _ } => OperatorAction::Unhandled,
+            State::Done => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn on_tick(&mut self) -> OperatorAction {
+        if self.last_inst.elapsed() <= self.timeout {
+            return match &self.state {
+                State::Pressed { count: _, device: _ } | State::Tapped { count: _, device: _ } => {
+                    OperatorAction::Undecided
+                }
+                State::Emitted { device: _, actions: _ } => OperatorAction::Unhandled,
+                State::Done => {
+                    unreachable!()
+                }
+            };
+        }
+
+        match &self.state {
+            State::Pressed { count, device } => {
+                // Held beyond the timeout.
+                let actions = self.hold.get(count - 1).unwrap_or(&self.taps[count - 1]).clone();
+                let emit = map_actions(&actions, device.clone(), KeyValue::Press);
+
+                self.state = State::Emitted {
+                    device: device.clone(),
+                    actions,
+                };
+
+                OperatorAction::Partial(emit, self.take_buffered())
+            }
+            State::Tapped { count, device: _ } => self.done_with_tap(*count),
+            State::Emitted { device: _, actions: _ } => OperatorAction::Unhandled,
+            State::Done => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn on_other(&mut self, event: &Event) -> OperatorAction {
+        match &self.state {
+            // Suppress when matching
+            State::Pressed { count: _, device: _ } | State::Tapped { count: _, device: _ } => {
+                self.buffered.push(event.clone());
+                OperatorAction::Undecided
+            }
+            State::Emitted { device: _, actions: _ } => OperatorAction::Unhandled,
+            State::Done => {
+                unreachable!()
+            }
+        }
+    }
+
+    // The state after the trigger key is pressed for the count'th time.
+    fn pressed(&mut self, count: usize, device: Rc<InputDeviceInfo>) -> State {
+        if count >= self.taps.len() && self.hold.len() < count {
+            // Nothing more to wait for, so the last tap action is held.
+            return State::Emitted {
+                device,
+                actions: self.taps[self.taps.len() - 1].clone(),
+            };
+        }
+
+        self.restart_timeout();
+
+        State::Pressed { count, device }
+    }
+
+    fn done_with_tap(&mut self, count: usize) -> OperatorAction {
+        let device = match &self.state {
+            State::Pressed { count: _, device } | State::Tapped { count: _, device } => device.clone(),
+            _ => unreachable!(),
+        };
+        let actions = &self.taps[count - 1];
+
+        let mut emit: Vec<Emit> = map_actions(actions, device.clone(), KeyValue::Press);
+        emit.extend(map_actions(actions, device, KeyValue::Release));
+
+        self.state = State::Done;
+
+        OperatorAction::Done(emit, self.take_buffered())
+    }
+
+    fn restart_timeout(&mut self) {
+        self.last_inst = Instant::now();
+        if let Err(err) = self.timeout_manager.set_timeout(self.timeout) {
+            error!("Failed to set_timeout: {err}");
+        }
+    }
+
+    fn take_buffered(&mut self) -> Vec<Event> {
+        let mut buffered = vec![];
+        swap(&mut buffered, &mut self.buffered);
+        buffered
+    }
+}
@@ -1,4 +1,5 @@
 use crate::config::application::OnlyOrNot;
+use crate::config::device::DeviceMatcher;
 use crate::config::expmap_operator::ExpmapAction;
 use crate::device::InputDeviceInfo;
 use crate::emit_handler::Emit;
@@ -39,6 +40,10 @@ pub fn map_actions(actions: &Vec<ExpmapAction>, device: Rc<InputDeviceInfo>, val
         .iter()
         .filter_map(|action| match action {
             ExpmapAction::Key(key) => Some(Emit::key_event(device.clone(), KeyEvent::new(*key, value))),
+            ExpmapAction::KeymapAction(action) if value == KeyValue::Press => {
+                Some(Emit::Single(Event::KeymapAction(action.clone())))
+            }
+            ExpmapAction::KeymapAction(_) => None,
         })
         .collect()
 }
@@ -49,4 +54,8 @@ pub struct OperatorEntry {
     pub operator: Box<dyn StaticOperator>,
     pub application: Option<OnlyOrNot>,
     pub title: Option<OnlyOrNot>,
+    pub device: Option<DeviceMatcher>,
+    pub mode: Option<Vec<String>>,
+    // Only start when no modifiers are held.
+    pub unmodified: bool,
 }
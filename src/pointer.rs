@@ -0,0 +1,111 @@
+use crate::config::pointer::{Axis, Pointer, WheelResolution};
+use crate::device::InputDeviceInfo;
+use crate::event::RelativeEvent;
+use evdev::RelativeAxisCode;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// The high resolution wheel value of one notch.
+const NOTCH: i32 = 120;
+
+// State of the transforms in `pointer`, per device and axis.
+pub struct PointerState {
+    // Fractions of pixels that are not emitted yet.
+    remainder: HashMap<(PathBuf, u16), f64>,
+    // High resolution wheel values that are not a full notch yet.
+    wheel_notch: HashMap<(PathBuf, u16), i32>,
+}
+
+impl PointerState {
+    pub fn new() -> Self {
+        PointerState {
+            remainder: HashMap::new(),
+            wheel_notch: HashMap::new(),
+        }
+    }
+
+    pub fn transform(
+        &mut self,
+        pointer: &Pointer,
+        device: &InputDeviceInfo,
+        event: &RelativeEvent,
+    ) -> Vec<RelativeEvent> {
+        let mut code = event.code;
+        let mut value = event.value;
+
+        if pointer.swap_xy {
+            if code == RelativeAxisCode::REL_X.0 {
+                code = RelativeAxisCode::REL_Y.0;
+            } else if code == RelativeAxisCode::REL_Y.0 {
+                code = RelativeAxisCode::REL_X.0;
+            }
+        }
+
+        if pointer.invert.iter().any(|axis| axis_codes(*axis).contains(&code)) {
+            value = -value;
+        }
+
+        if code == RelativeAxisCode::REL_X.0 || code == RelativeAxisCode::REL_Y.0 {
+            let remainder = self.remainder.entry((device.path.clone(), code)).or_default();
+            let moved = *remainder
+                + pointer.sensitivity * (value as f64).signum() * (value.abs() as f64).powf(pointer.acceleration);
+            *remainder = moved.fract();
+
+            return to_events(&[(code, moved.trunc() as i32)]);
+        }
+
+        match (pointer.wheel, wheel_pair(code)) {
+            (Some(WheelResolution::LowRes), Some((hi_res, low_res))) => {
+                if code == hi_res {
+                    // Made from the normal events
+                    vec![]
+                } else {
+                    to_events(&[(hi_res, value * NOTCH), (low_res, value)])
+                }
+            }
+            (Some(WheelResolution::HiRes), Some((hi_res, low_res))) => {
+                if code == low_res && !device.hi_res_wheels.contains(&RelativeAxisCode(hi_res)) {
+                    // The device only has the normal wheel, so it's scrolled in whole notches
+                    to_events(&[(hi_res, value * NOTCH), (low_res, value)])
+                } else if code == low_res {
+                    // Made from the high resolution events
+                    vec![]
+                } else {
+                    let notch = self.wheel_notch.entry((device.path.clone(), code)).or_default();
+                    let total = *notch + value;
+                    *notch = total % NOTCH;
+
+                    to_events(&[(hi_res, value), (low_res, total / NOTCH)])
+                }
+            }
+            _ => to_events(&[(code, value)]),
+        }
+    }
+}
+
+fn axis_codes(axis: Axis) -> Vec<u16> {
+    match axis {
+        Axis::X => vec![RelativeAxisCode::REL_X.0],
+        Axis::Y => vec![RelativeAxisCode::REL_Y.0],
+        Axis::Wheel => vec![RelativeAxisCode::REL_WHEEL.0, RelativeAxisCode::REL_WHEEL_HI_RES.0],
+        Axis::Hwheel => vec![RelativeAxisCode::REL_HWHEEL.0, RelativeAxisCode::REL_HWHEEL_HI_RES.0],
+    }
+}
+
+// The high resolution and normal code of a wheel.
+fn wheel_pair(code: u16) -> Option<(u16, u16)> {
+    [
+        (RelativeAxisCode::REL_WHEEL_HI_RES.0, RelativeAxisCode::REL_WHEEL.0),
+        (RelativeAxisCode::REL_HWHEEL_HI_RES.0, RelativeAxisCode::REL_HWHEEL.0),
+    ]
+    .into_iter()
+    .find(|(hi_res, low_res)| code == *hi_res || code == *low_res)
+}
+
+fn to_events(values: &[(u16, i32)]) -> Vec<RelativeEvent> {
+    values
+        .iter()
+        .filter(|(_, value)| *value != 0)
+        .map(|(code, value)| RelativeEvent::new_with(*code, *value))
+        .collect()
+}
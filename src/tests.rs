@@ -7,7 +7,10 @@ use crate::config::Config;
 use crate::device::InputDeviceInfo;
 use crate::event::{Event, KeyEvent, KeyValue, RelativeEvent};
 use crate::event_handler::EventHandler;
-use evdev::{KeyCode as Key, RelativeAxisCode};
+use crate::input_filter::InputFilter;
+use crate::operator_handler::OperatorHandler;
+use crate::timeout_manager::TimeoutManager;
+use evdev::{KeyCode as Key, LedCode, RelativeAxisCode};
 use indoc::indoc;
 use nix::sys::timerfd::{ClockId, TimerFd, TimerFlags};
 use std::path::PathBuf;
@@ -19,18 +22,26 @@ use std::time::Duration;
 /// Test cases are placed in the file of the most specific feature they test.
 /// With the following definition of specific (ordered by most specific first):
 ///
+///     Debounce
+///     Slow keys and bounce keys
 ///     Escape Next Key
 ///     Any key
+///     Operator Auto Shift
 ///     Operator Double Tap
+///     Operator Sequences
+///     Operator Tap Dance
 ///     Operator Chords
 ///     Operator Handler
 ///     Virtual modifiers
 ///     Disguised events input (i.e. transformation of relative event to pseudo keys)
+///     Multipurpose keys (home-row mods)
 ///     Multipurpose keys (tap-preferred)
 ///     Multipurpose keys (hold-preferred)
+///     One-shot keys
 ///     PressRelease keys
 ///     Modmap key-to-key
 ///     Modifier triggers
+///     Caps word
 ///     Nested remap in keymap
 ///     Keymap with_mark
 ///     Keymap
@@ -70,6 +81,7 @@ pub fn get_input_device_info() -> Rc<InputDeviceInfo> {
         path: PathBuf::from("/dev/input/event0"),
         vendor: 0x1234,
         product: 0x5678,
+        hi_res_wheels: vec![RelativeAxisCode::REL_WHEEL_HI_RES, RelativeAxisCode::REL_HWHEEL_HI_RES],
     })
 }
 
@@ -388,6 +400,7 @@ fn test_device_override() {
                 path: PathBuf::from("/dev/input/event0"),
                 vendor: 0x1234,
                 product: 0x5678,
+                hi_res_wheels: vec![],
             }),
             KeyEvent::new(Key::KEY_A, KeyValue::Press),
         )],
@@ -409,6 +422,7 @@ fn test_device_override() {
                 path: PathBuf::from("/dev/input/event1"),
                 vendor: 0x1234,
                 product: 0x5678,
+                hi_res_wheels: vec![],
             }),
             KeyEvent::new(Key::KEY_A, KeyValue::Press),
         )],
@@ -563,6 +577,26 @@ fn test_keymap_repeat() {
     );
 }
 
+#[test]
+fn test_release_held_keys_on_reload() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        keymap:
+          - remap:
+              C-r: { action: reload_config }
+        "});
+    handler.assert(
+        vec![Event::key_press(Key::KEY_LEFTCTRL)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTCTRL, KeyValue::Press))],
+    );
+
+    // The output released Ctrl on reload, so it's no longer held until it's pressed again.
+    handler.release_held_keys();
+    handler.assert(
+        vec![Event::key_press(Key::KEY_R)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_R, KeyValue::Press))],
+    );
+}
+
 pub fn parse_config_for_test(str: &str) -> Config {
     let mut config: Config = serde_yaml::from_str(str).unwrap();
     config.keymap_table = build_keymap_table(&config.keymap);
@@ -604,7 +638,25 @@ impl EventHandlerForTest {
     pub fn new_with_current_application(config_yaml: &str, current_application: Option<String>) -> Self {
         let timer = TimerFd::new(ClockId::CLOCK_MONOTONIC, TimerFlags::empty()).unwrap();
         let config = parse_config_for_test(config_yaml);
-        let event_handler = EventHandler::new(timer, &config.default_mode, Duration::from_micros(0), None);
+        let timeout_manager = Rc::new(TimeoutManager::new());
+        let operator_handler = if config.experimental_map.is_empty() && config.auto_shift.is_none() {
+            None
+        } else {
+            Some(OperatorHandler::new(&config.experimental_map, &config.auto_shift, timeout_manager.clone()))
+        };
+        let input_filter = if config.slow_keys.is_empty() && config.bounce_keys.is_empty() {
+            None
+        } else {
+            Some(InputFilter::new(&config.slow_keys, &config.bounce_keys, timeout_manager.clone()))
+        };
+        let event_handler = EventHandler::new(
+            timer,
+            timeout_manager,
+            &config.default_mode,
+            Duration::from_micros(0),
+            operator_handler,
+            input_filter,
+        );
 
         Self {
             event_handler,
@@ -614,14 +666,20 @@ impl EventHandlerForTest {
     }
 
     pub fn assert(&mut self, events: Vec<Event>, actions: Vec<Action>) {
-        assert_eq!(
-            format!("{actions:?}"),
-            format!(
-                "{:?}",
-                self.event_handler
-                    .on_events(events, &self.config, &mut self.wmclient)
-                    .unwrap()
-            )
-        );
+        assert_eq!(format!("{actions:?}"), format!("{:?}", self.on_events(events)));
+    }
+
+    pub fn release_held_keys(&mut self) {
+        self.event_handler.release_held_keys();
+    }
+
+    pub fn set_lock(&mut self, led: LedCode, on: bool) {
+        self.event_handler.set_lock(led, on);
+    }
+
+    pub fn on_events(&mut self, events: Vec<Event>) -> Vec<Action> {
+        self.event_handler
+            .on_events(events, &self.config, &mut self.wmclient)
+            .unwrap()
     }
 }
@@ -0,0 +1,162 @@
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::tests::EventHandlerForTest;
+use evdev::KeyCode as Key;
+use indoc::indoc;
+use std::thread::sleep;
+use std::time::Duration;
+
+fn shifted(key: Key, value: KeyValue) -> Vec<Action> {
+    vec![
+        Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Press)),
+        Action::KeyEvent(KeyEvent::new(key, value)),
+        Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Release)),
+    ]
+}
+
+#[test]
+fn test_caps_word_ends_at_space() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        keymap:
+          - remap:
+              CAPSLOCK: { caps_word: true }
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_CAPSLOCK)], vec![]);
+    handler.assert(vec![Event::key_press(Key::KEY_A)], shifted(Key::KEY_A, KeyValue::Press));
+    handler.assert(vec![Event::key_repeat(Key::KEY_A)], shifted(Key::KEY_A, KeyValue::Repeat));
+    handler.assert(
+        vec![Event::key_release(Key::KEY_A)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Release))],
+    );
+
+    // Continues the word, without shift
+    handler.assert(
+        vec![Event::key_press(Key::KEY_1), Event::key_press(Key::KEY_MINUS)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_1, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_MINUS, KeyValue::Press)),
+        ],
+    );
+    handler.assert(vec![Event::key_press(Key::KEY_B)], shifted(Key::KEY_B, KeyValue::Press));
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_SPACE), Event::key_press(Key::KEY_C)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_SPACE, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_C, KeyValue::Press)),
+        ],
+    );
+}
+
+#[test]
+fn test_caps_word_with_options() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        caps_word:
+          continue_keys: [BACKSPACE]
+          underscore: true
+          timeout_millis: 10
+        modmap:
+          - remap:
+              CAPSLOCK:
+                skip_key_event: true
+                press: { caps_word: true }
+        "});
+
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_CAPSLOCK),
+            Event::key_release(Key::KEY_CAPSLOCK),
+        ],
+        vec![],
+    );
+    handler.assert(vec![Event::key_press(Key::KEY_MINUS)], shifted(Key::KEY_MINUS, KeyValue::Press));
+    handler.assert(
+        vec![Event::key_press(Key::KEY_BACKSPACE)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_BACKSPACE, KeyValue::Press))],
+    );
+
+    sleep(Duration::from_millis(20));
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_A)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press))],
+    );
+}
+
+#[test]
+fn test_caps_word_ends_at_digit_when_not_continuing() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        caps_word:
+          continue_keys: []
+        keymap:
+          - remap:
+              CAPSLOCK: { caps_word: true }
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_CAPSLOCK)], vec![]);
+    handler.assert(
+        vec![Event::key_press(Key::KEY_1), Event::key_press(Key::KEY_A)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_1, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+        ],
+    );
+}
+
+#[test]
+fn test_caps_word_with_shift_held() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        keymap:
+          - remap:
+              CAPSLOCK: { caps_word: true }
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_CAPSLOCK)], vec![]);
+    handler.assert(
+        vec![Event::key_press(Key::KEY_LEFTSHIFT), Event::key_press(Key::KEY_A)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+        ],
+    );
+}
+
+#[test]
+fn test_caps_word_ends_at_shortcut() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        keymap:
+          - remap:
+              CAPSLOCK: { caps_word: true }
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_CAPSLOCK)], vec![]);
+    handler.assert(
+        vec![Event::key_press(Key::KEY_LEFTCTRL), Event::key_press(Key::KEY_C)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTCTRL, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_C, KeyValue::Press)),
+        ],
+    );
+    handler.assert(
+        vec![
+            Event::key_release(Key::KEY_C),
+            Event::key_release(Key::KEY_LEFTCTRL),
+            Event::key_press(Key::KEY_A),
+        ],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_C, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTCTRL, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+        ],
+    );
+
+    handler.assert(vec![Event::key_press(Key::KEY_CAPSLOCK)], vec![]);
+    handler.assert(
+        vec![Event::key_press(Key::KEY_LEFTALT), Event::key_press(Key::KEY_F)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTALT, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_F, KeyValue::Press)),
+        ],
+    );
+}
@@ -0,0 +1,95 @@
+use crate::debounce::Debouncer;
+use crate::device::InputDeviceInfo;
+use crate::tests::{get_input_device_info, parse_config_for_test};
+use evdev::{EventType, InputEvent, KeyCode as Key};
+use indoc::indoc;
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+
+fn key(key: Key, value: i32) -> InputEvent {
+    InputEvent::new(EventType::KEY.0, key.code(), value)
+}
+
+fn get_debouncer(config: &str) -> Debouncer {
+    Debouncer::new(&parse_config_for_test(config).debounce)
+}
+
+fn filter(debouncer: &mut Debouncer, device: &InputDeviceInfo, events: Vec<InputEvent>) -> Vec<(u16, i32)> {
+    debouncer
+        .filter_events(device, events.into_iter())
+        .iter()
+        .map(|event| (event.code(), event.value()))
+        .collect()
+}
+
+#[test]
+fn test_debounce_chatter() {
+    let mut debouncer = get_debouncer(indoc! {"
+        debounce:
+          - window: 10
+        "});
+    let device = get_input_device_info();
+
+    assert_eq!(
+        filter(
+            &mut debouncer,
+            &device,
+            vec![
+                key(Key::KEY_A, 1),
+                key(Key::KEY_A, 0),
+                key(Key::KEY_A, 1),
+                key(Key::KEY_A, 2),
+                key(Key::KEY_A, 0),
+                key(Key::KEY_B, 1),
+            ]
+        ),
+        vec![(Key::KEY_A.code(), 1), (Key::KEY_A.code(), 0), (Key::KEY_B.code(), 1)]
+    );
+
+    sleep(Duration::from_millis(10));
+
+    assert_eq!(filter(&mut debouncer, &device, vec![key(Key::KEY_A, 1)]), vec![(Key::KEY_A.code(), 1)]);
+}
+
+#[test]
+fn test_debounce_keys_and_device() {
+    let mut debouncer = get_debouncer(indoc! {"
+        debounce:
+          - device:
+              only: Some Device
+            keys: [A]
+            window_millis: 10
+        "});
+    let device = get_input_device_info();
+    let other_device = InputDeviceInfo {
+        name: "Other Device".into(),
+        path: PathBuf::from("/dev/input/event1"),
+        vendor: 0x1234,
+        product: 0x5678,
+        hi_res_wheels: vec![],
+    };
+    let events = || {
+        vec![
+            key(Key::KEY_A, 1),
+            key(Key::KEY_A, 0),
+            key(Key::KEY_B, 1),
+            key(Key::KEY_B, 0),
+            key(Key::KEY_A, 1),
+            key(Key::KEY_B, 1),
+        ]
+    };
+
+    assert_eq!(
+        filter(&mut debouncer, &device, events()),
+        vec![
+            (Key::KEY_A.code(), 1),
+            (Key::KEY_A.code(), 0),
+            (Key::KEY_B.code(), 1),
+            (Key::KEY_B.code(), 0),
+            (Key::KEY_B.code(), 1),
+        ]
+    );
+
+    assert_eq!(filter(&mut debouncer, &other_device, events()).len(), 6);
+}
@@ -2,10 +2,11 @@ use crate::action::Action;
 use crate::event::Event;
 use crate::event::RelativeEvent;
 use crate::event::{KeyEvent, KeyValue};
-use crate::tests::assert_actions;
+use crate::tests::{assert_actions, EventHandlerForTest};
 use evdev::KeyCode as Key;
 use evdev::RelativeAxisCode;
 use indoc::indoc;
+use std::thread::sleep;
 use std::time::Duration;
 
 #[test]
@@ -239,3 +240,71 @@ fn test_opt_in_mouse_movement_interrupts_multi_purpose_key() {
         ],
     )
 }
+
+#[test]
+fn test_relative_events_in_modmap() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        relative_release_ms: 50
+        modmap:
+          - remap:
+              XUpScroll: Shift_L
+        keymap:
+          - remap:
+              Shift-XDownScroll: c
+        "});
+
+    handler.assert(
+        vec![
+            Event::relative(RelativeAxisCode::REL_WHEEL.0, 1),
+            Event::relative(RelativeAxisCode::REL_WHEEL.0, 1),
+        ],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Repeat)),
+        ],
+    );
+
+    // Held while scrolling up
+    handler.assert(
+        vec![Event::relative(RelativeAxisCode::REL_WHEEL.0, -1)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_C, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_C, KeyValue::Release)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Press)),
+            Action::Delay(Duration::from_nanos(0)),
+        ],
+    );
+
+    // Not released before the timeout
+    handler.assert(vec![Event::Tick], vec![]);
+
+    sleep(Duration::from_millis(60));
+
+    handler.assert(vec![Event::Tick], vec![Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Release))]);
+}
+
+#[test]
+fn test_relative_events_in_modmap_multi_purpose_key() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        relative_release_ms: 50
+        modmap:
+          - remap:
+              XRightScroll:
+                alone: VolumeUp
+                held: Ctrl_L
+        "});
+
+    handler.assert(vec![Event::relative(RelativeAxisCode::REL_HWHEEL.0, 1)], vec![]);
+
+    sleep(Duration::from_millis(60));
+
+    handler.assert(
+        vec![Event::Tick],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_VOLUMEUP, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_VOLUMEUP, KeyValue::Release)),
+        ],
+    );
+}
@@ -0,0 +1,58 @@
+use crate::config::emergency_escape::EmergencyEscape;
+use crate::emergency_escape::EscapeDetector;
+use evdev::{EventType, InputEvent, KeyCode as Key};
+use std::time::Duration;
+
+fn key(key: Key, value: i32) -> InputEvent {
+    InputEvent::new(EventType::KEY.0, key.code(), value)
+}
+
+#[test]
+fn test_emergency_escape_after_holding() {
+    let config = EmergencyEscape {
+        keys: vec![Key::KEY_LEFTSHIFT, Key::KEY_ESC],
+        hold: Duration::from_millis(20),
+    };
+    let mut detector = EscapeDetector::default();
+
+    assert!(!detector.on_events(&[key(Key::KEY_LEFTSHIFT, 1)], &config));
+    assert!(!detector.on_events(&[key(Key::KEY_ESC, 1)], &config));
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(detector.on_events(&[key(Key::KEY_ESC, 2)], &config));
+}
+
+#[test]
+fn test_emergency_escape_restarts_on_release() {
+    let config = EmergencyEscape {
+        keys: vec![Key::KEY_LEFTSHIFT, Key::KEY_ESC],
+        hold: Duration::from_millis(20),
+    };
+    let mut detector = EscapeDetector::default();
+
+    assert!(!detector.on_events(&[key(Key::KEY_LEFTSHIFT, 1), key(Key::KEY_ESC, 1)], &config));
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(!detector.on_events(&[key(Key::KEY_ESC, 0)], &config));
+    assert!(!detector.on_events(&[key(Key::KEY_ESC, 1)], &config));
+    assert!(!detector.on_events(&[key(Key::KEY_ESC, 2)], &config));
+}
+
+#[test]
+fn test_emergency_escape_without_key_repeat() {
+    let config = EmergencyEscape {
+        keys: vec![Key::KEY_LEFTSHIFT, Key::KEY_ESC],
+        hold: Duration::from_millis(20),
+    };
+    let mut detector = EscapeDetector::default();
+
+    assert!(!detector.on_events(&[key(Key::KEY_LEFTSHIFT, 1), key(Key::KEY_ESC, 1)], &config));
+    assert_eq!(detector.take_timeout(&config), Some(Duration::from_millis(20)));
+    assert_eq!(detector.take_timeout(&config), None);
+    assert!(!detector.is_held(&config));
+
+    // Checked on the timeout without any more events from the device
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(detector.is_held(&config));
+
+    assert!(!detector.on_events(&[key(Key::KEY_ESC, 0)], &config));
+    assert!(!detector.is_held(&config));
+}
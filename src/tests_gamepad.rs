@@ -0,0 +1,110 @@
+use crate::action::Action;
+use crate::config::gamepad::Gamepad;
+use crate::config::key::parse_key;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::event_handler::{PRESS, RELEASE};
+use crate::gamepad::AxisRecognizer;
+use crate::tests::EventHandlerForTest;
+use evdev::{AbsInfo, AbsoluteAxisCode, EventType, InputEvent, KeyCode as Key};
+use indoc::indoc;
+use std::time::Duration;
+
+fn get_recognizer() -> AxisRecognizer {
+    AxisRecognizer::new(
+        vec![
+            (AbsoluteAxisCode::ABS_X, AbsInfo::new(0, -32768, 32767, 0, 0, 0)),
+            (AbsoluteAxisCode::ABS_Z, AbsInfo::new(0, 0, 255, 0, 0, 0)),
+            (AbsoluteAxisCode::ABS_HAT0Y, AbsInfo::new(0, -1, 1, 0, 0, 0)),
+        ]
+        .into_iter(),
+    )
+}
+
+// The pseudo-keys of moving an axis
+fn axis(recognizer: &mut AxisRecognizer, axis: AbsoluteAxisCode, value: i32) -> Vec<(Key, i32)> {
+    let event = InputEvent::new(EventType::ABSOLUTE.0, axis.0, value);
+    recognizer
+        .on_event(&event, &Gamepad::default())
+        .iter()
+        .map(|event| (Key(event.code()), event.value()))
+        .collect()
+}
+
+fn key(name: &str) -> Key {
+    parse_key(name).unwrap()
+}
+
+#[test]
+fn test_gamepad_axis_with_hysteresis() {
+    let mut recognizer = get_recognizer();
+
+    assert_eq!(axis(&mut recognizer, AbsoluteAxisCode::ABS_X, 10000), vec![]);
+    assert_eq!(axis(&mut recognizer, AbsoluteAxisCode::ABS_X, 20000), vec![(key("XABS_X_PLUS"), PRESS)]);
+    // Still pressed between the thresholds
+    assert_eq!(axis(&mut recognizer, AbsoluteAxisCode::ABS_X, 12000), vec![]);
+    assert_eq!(axis(&mut recognizer, AbsoluteAxisCode::ABS_X, 5000), vec![(key("XABS_X_PLUS"), RELEASE)]);
+
+    // To the other side at once
+    assert_eq!(axis(&mut recognizer, AbsoluteAxisCode::ABS_X, 30000), vec![(key("XABS_X_PLUS"), PRESS)]);
+    assert_eq!(
+        axis(&mut recognizer, AbsoluteAxisCode::ABS_X, -30000),
+        vec![(key("XABS_X_PLUS"), RELEASE), (key("XABS_X_MINUS"), PRESS)]
+    );
+}
+
+#[test]
+fn test_gamepad_trigger_and_hat() {
+    let mut recognizer = get_recognizer();
+
+    // A trigger rests at the minimum
+    assert_eq!(axis(&mut recognizer, AbsoluteAxisCode::ABS_Z, 100), vec![]);
+    assert_eq!(axis(&mut recognizer, AbsoluteAxisCode::ABS_Z, 200), vec![(key("XABS_Z_PLUS"), PRESS)]);
+    assert_eq!(axis(&mut recognizer, AbsoluteAxisCode::ABS_Z, 0), vec![(key("XABS_Z_PLUS"), RELEASE)]);
+
+    assert_eq!(axis(&mut recognizer, AbsoluteAxisCode::ABS_HAT0Y, -1), vec![(key("XABS_HAT0Y_MINUS"), PRESS)]);
+    assert_eq!(axis(&mut recognizer, AbsoluteAxisCode::ABS_HAT0Y, 0), vec![(key("XABS_HAT0Y_MINUS"), RELEASE)]);
+}
+
+#[test]
+fn test_axis_action_resets_on_release() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        enable_gamepad: true
+        keymap:
+          - remap:
+              a: { axis: { ABS_X: -32767, hat0y: -1 } }
+              s: BTN_SOUTH
+        "});
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_A)],
+        vec![
+            Action::GamepadAxis(AbsoluteAxisCode::ABS_X, -32767),
+            Action::GamepadAxis(AbsoluteAxisCode::ABS_HAT0Y, -1),
+        ],
+    );
+    handler.assert(
+        vec![Event::key_repeat(Key::KEY_A)],
+        vec![
+            Action::GamepadAxis(AbsoluteAxisCode::ABS_X, -32767),
+            Action::GamepadAxis(AbsoluteAxisCode::ABS_HAT0Y, -1),
+        ],
+    );
+    handler.assert(
+        vec![Event::key_release(Key::KEY_A)],
+        vec![
+            Action::GamepadAxis(AbsoluteAxisCode::ABS_X, 0),
+            Action::GamepadAxis(AbsoluteAxisCode::ABS_HAT0Y, 0),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Release)),
+        ],
+    );
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_S)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::BTN_SOUTH, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::BTN_SOUTH, KeyValue::Release)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::Delay(Duration::from_nanos(0)),
+        ],
+    );
+}
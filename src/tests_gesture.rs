@@ -0,0 +1,175 @@
+use crate::action::Action;
+use crate::config::gestures::Gestures;
+use crate::config::key::parse_key;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::event_handler::PRESS;
+use crate::gesture::GestureRecognizer;
+use crate::tests::assert_actions;
+use evdev::{AbsoluteAxisCode, EventType, InputEvent, KeyCode as Key, SynchronizationCode};
+use indoc::indoc;
+use std::time::Duration;
+
+fn abs(code: AbsoluteAxisCode, value: i32) -> InputEvent {
+    InputEvent::new(EventType::ABSOLUTE.0, code.0, value)
+}
+
+// A frame where the fingers are put down or moved to the positions
+fn frame(touches: &[(i32, i32, i32)]) -> Vec<InputEvent> {
+    let mut events = vec![];
+    for (slot, x, y) in touches {
+        events.push(abs(AbsoluteAxisCode::ABS_MT_SLOT, *slot));
+        events.push(abs(AbsoluteAxisCode::ABS_MT_TRACKING_ID, *slot));
+        events.push(abs(AbsoluteAxisCode::ABS_MT_POSITION_X, *x));
+        events.push(abs(AbsoluteAxisCode::ABS_MT_POSITION_Y, *y));
+    }
+    events.push(InputEvent::new(EventType::SYNCHRONIZATION.0, SynchronizationCode::SYN_REPORT.0, 0));
+    events
+}
+
+fn lift(slots: &[i32]) -> Vec<InputEvent> {
+    let mut events = vec![];
+    for slot in slots {
+        events.push(abs(AbsoluteAxisCode::ABS_MT_SLOT, *slot));
+        events.push(abs(AbsoluteAxisCode::ABS_MT_TRACKING_ID, -1));
+    }
+    events.push(InputEvent::new(EventType::SYNCHRONIZATION.0, SynchronizationCode::SYN_REPORT.0, 0));
+    events
+}
+
+// The pressed gesture
+fn recognize(recognizer: &mut GestureRecognizer, events: Vec<InputEvent>) -> Option<u16> {
+    let gestures = Gestures::default();
+    events
+        .iter()
+        .flat_map(|event| recognizer.on_event(event, &gestures))
+        .filter(|event| event.value() == PRESS)
+        .map(|event| event.code())
+        .next()
+}
+
+fn key(name: &str) -> u16 {
+    parse_key(name).unwrap().code()
+}
+
+#[test]
+fn test_gesture_swipe() {
+    let mut recognizer = GestureRecognizer::default();
+
+    assert_eq!(recognize(&mut recognizer, frame(&[(0, 1000, 1000)])), None);
+    assert_eq!(recognize(&mut recognizer, frame(&[(1, 1100, 1000), (2, 1200, 1000)])), None);
+    assert_eq!(recognize(&mut recognizer, frame(&[(0, 800, 1000), (1, 900, 1000), (2, 1000, 1000)])), None);
+    assert_eq!(
+        recognize(&mut recognizer, frame(&[(0, 600, 1050), (1, 700, 1050), (2, 800, 1050)])),
+        Some(key("XLEFTSWIPE3"))
+    );
+
+    // Only once until all fingers are lifted
+    assert_eq!(recognize(&mut recognizer, frame(&[(0, 100, 1050), (1, 200, 1050), (2, 300, 1050)])), None);
+    assert_eq!(recognize(&mut recognizer, lift(&[0, 1, 2])), None);
+
+    assert_eq!(
+        recognize(&mut recognizer, frame(&[(0, 1000, 1000), (1, 1100, 1000), (2, 1200, 1000), (3, 1300, 1000)])),
+        None
+    );
+    assert_eq!(
+        recognize(&mut recognizer, frame(&[(0, 1000, 600), (1, 1100, 600), (2, 1200, 600), (3, 1300, 600)])),
+        Some(key("XUPSWIPE4"))
+    );
+}
+
+#[test]
+fn test_gesture_pinch() {
+    let mut recognizer = GestureRecognizer::default();
+
+    assert_eq!(recognize(&mut recognizer, frame(&[(0, 1000, 1000), (1, 1200, 1000)])), None);
+    assert_eq!(recognize(&mut recognizer, frame(&[(0, 900, 1000), (1, 1300, 1000)])), None);
+    assert_eq!(recognize(&mut recognizer, frame(&[(0, 700, 1000), (1, 1500, 1000)])), Some(key("XPINCHOUT")));
+    assert_eq!(recognize(&mut recognizer, lift(&[0, 1])), None);
+
+    // Two finger scrolling isn't a pinch
+    assert_eq!(recognize(&mut recognizer, frame(&[(0, 1000, 1000), (1, 1200, 1000)])), None);
+    assert_eq!(recognize(&mut recognizer, frame(&[(0, 1000, 500), (1, 1200, 500)])), None);
+    assert_eq!(recognize(&mut recognizer, frame(&[(0, 1050, 450), (1, 1150, 450)])), None);
+    assert_eq!(recognize(&mut recognizer, frame(&[(0, 1099, 450), (1, 1101, 450)])), None);
+}
+
+// The events of the frame that go to the mirror device
+fn mirror(recognizer: &mut GestureRecognizer, events: Vec<InputEvent>, gestures: &Gestures) -> Vec<(u16, i32)> {
+    for event in &events {
+        recognizer.on_event(event, gestures);
+    }
+    let frame = events
+        .into_iter()
+        .filter(|event| event.event_type() != EventType::SYNCHRONIZATION)
+        .collect();
+    recognizer
+        .mirror_frame(frame, gestures)
+        .iter()
+        .map(|event| (event.code(), event.value()))
+        .collect()
+}
+
+#[test]
+fn test_gesture_swipes_passed_on() {
+    let mut recognizer = GestureRecognizer::default();
+    let gestures = Gestures::default();
+
+    let touches = frame(&[(0, 1000, 1000), (1, 1100, 1000), (2, 1200, 1000)]);
+    let expected: Vec<_> = touches
+        .iter()
+        .filter(|event| event.event_type() != EventType::SYNCHRONIZATION)
+        .map(|event| (event.code(), event.value()))
+        .collect();
+    assert_eq!(mirror(&mut recognizer, touches, &gestures), expected);
+}
+
+#[test]
+fn test_gesture_consume_swipes() {
+    let mut recognizer = GestureRecognizer::default();
+    let gestures = Gestures {
+        consume_swipes: true,
+        ..Gestures::default()
+    };
+    let slot = AbsoluteAxisCode::ABS_MT_SLOT.0;
+    let tracking_id = AbsoluteAxisCode::ABS_MT_TRACKING_ID.0;
+
+    // Two fingers are passed on for scrolling
+    assert_eq!(mirror(&mut recognizer, frame(&[(0, 1000, 1000), (1, 1100, 1000)]), &gestures).len(), 8);
+
+    // The third finger lifts them on the mirror device
+    let mut lifted = mirror(&mut recognizer, frame(&[(2, 1200, 1000)]), &gestures);
+    assert_eq!(lifted.pop(), Some((slot, 2)));
+    lifted.sort();
+    assert_eq!(lifted, vec![(slot, 0), (slot, 1), (tracking_id, -1), (tracking_id, -1)]);
+
+    // The rest of the touch is withheld, even with fewer fingers
+    assert_eq!(mirror(&mut recognizer, frame(&[(0, 600, 1000), (1, 700, 1000), (2, 800, 1000)]), &gestures), vec![]);
+    assert_eq!(mirror(&mut recognizer, lift(&[1, 2]), &gestures), vec![]);
+    assert_eq!(mirror(&mut recognizer, lift(&[0]), &gestures), vec![]);
+
+    // The next touch is passed on
+    assert_eq!(mirror(&mut recognizer, frame(&[(0, 1000, 1000)]), &gestures).len(), 4);
+}
+
+#[test]
+fn test_gesture_in_keymap() {
+    assert_actions(
+        indoc! {"
+        keymap:
+          - remap:
+              XRightSwipe3: C-Tab
+        "},
+        vec![
+            Event::key_press(Key(key("XRIGHTSWIPE3"))),
+            Event::key_release(Key(key("XRIGHTSWIPE3"))),
+        ],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTCTRL, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_TAB, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_TAB, KeyValue::Release)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTCTRL, KeyValue::Release)),
+        ],
+    )
+}
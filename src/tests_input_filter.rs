@@ -0,0 +1,138 @@
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::tests::EventHandlerForTest;
+use evdev::KeyCode as Key;
+use indoc::indoc;
+use std::thread::sleep;
+use std::time::Duration;
+
+static DELAY: Duration = Duration::from_millis(10);
+
+fn key(key: Key, value: KeyValue) -> Action {
+    Action::KeyEvent(KeyEvent::new(key, value))
+}
+
+#[test]
+fn test_slow_keys_held() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        slow_keys:
+          - delay: 10
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_A)], vec![]);
+    handler.assert(vec![Event::key_repeat(Key::KEY_A)], vec![]);
+    handler.assert(vec![Event::Tick], vec![]);
+
+    sleep(DELAY);
+
+    handler.assert(vec![Event::Tick], vec![key(Key::KEY_A, KeyValue::Press)]);
+    handler.assert(vec![Event::key_repeat(Key::KEY_A)], vec![key(Key::KEY_A, KeyValue::Repeat)]);
+    handler.assert(vec![Event::key_release(Key::KEY_A)], vec![key(Key::KEY_A, KeyValue::Release)]);
+}
+
+#[test]
+fn test_slow_keys_released_early() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        slow_keys:
+          - delay_millis: 10
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_A), Event::key_release(Key::KEY_A)], vec![]);
+
+    sleep(DELAY);
+
+    handler.assert(vec![Event::Tick], vec![]);
+}
+
+#[test]
+fn test_slow_keys_released_before_tick() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        slow_keys:
+          - delay: 10
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_A)], vec![]);
+
+    sleep(DELAY);
+
+    // The release is read before the tick that would accept the press.
+    handler.assert(
+        vec![Event::key_release(Key::KEY_A), Event::Tick],
+        vec![key(Key::KEY_A, KeyValue::Press), key(Key::KEY_A, KeyValue::Release)],
+    );
+}
+
+#[test]
+fn test_slow_keys_before_modmap() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        slow_keys:
+          - delay: 10
+        modmap:
+          - remap:
+              A: B
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_A)], vec![]);
+
+    sleep(DELAY);
+
+    handler.assert(vec![Event::Tick], vec![key(Key::KEY_B, KeyValue::Press)]);
+    handler.assert(vec![Event::key_release(Key::KEY_A)], vec![key(Key::KEY_B, KeyValue::Release)]);
+}
+
+#[test]
+fn test_bounce_keys() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        bounce_keys:
+          - delay: 10
+        "});
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_A), Event::key_release(Key::KEY_A)],
+        vec![key(Key::KEY_A, KeyValue::Press), key(Key::KEY_A, KeyValue::Release)],
+    );
+
+    // Bounced
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_A),
+            Event::key_repeat(Key::KEY_A),
+            Event::key_release(Key::KEY_A),
+        ],
+        vec![],
+    );
+
+    // Other keys aren't affected
+    handler.assert(vec![Event::key_press(Key::KEY_B)], vec![key(Key::KEY_B, KeyValue::Press)]);
+
+    sleep(DELAY);
+
+    handler.assert(vec![Event::key_press(Key::KEY_A)], vec![key(Key::KEY_A, KeyValue::Press)]);
+}
+
+#[test]
+fn test_input_filter_device() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        slow_keys:
+          - device:
+              only: Other Device
+            delay: 10
+        bounce_keys:
+          - device:
+              not: Some Device
+            delay: 10
+        "});
+
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_A),
+            Event::key_release(Key::KEY_A),
+            Event::key_press(Key::KEY_A),
+        ],
+        vec![
+            key(Key::KEY_A, KeyValue::Press),
+            key(Key::KEY_A, KeyValue::Release),
+            key(Key::KEY_A, KeyValue::Press),
+        ],
+    );
+}
@@ -0,0 +1,59 @@
+use crate::led::Leds;
+use crate::tests::parse_config_for_test;
+use evdev::{EventType, InputEvent, LedCode};
+use indoc::indoc;
+
+fn led(led: LedCode, lit: bool) -> InputEvent {
+    InputEvent::new(EventType::LED.0, led.0, lit as i32)
+}
+
+fn assert_led_events(actual: Vec<InputEvent>, expected: Vec<InputEvent>) {
+    let actual: Vec<_> = actual.iter().map(|e| (e.code(), e.value())).collect();
+    let expected: Vec<_> = expected.iter().map(|e| (e.code(), e.value())).collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_led_indicator_for_mode() {
+    let config = parse_config_for_test(indoc! {"
+        led_indicators:
+          - led: scrolllock
+            mode: nav
+        "});
+    let mut leds = Leds::new(&config, "xremap");
+
+    leds.update_indicators(&config.led_indicators, "default", false);
+    assert_led_events(leds.take_changes(), vec![led(LedCode::LED_SCROLLL, false)]);
+
+    leds.update_indicators(&config.led_indicators, "nav", false);
+    assert_led_events(leds.take_changes(), vec![led(LedCode::LED_SCROLLL, true)]);
+
+    // Nothing changed
+    leds.update_indicators(&config.led_indicators, "nav", false);
+    assert_led_events(leds.take_changes(), vec![]);
+
+    leds.update_indicators(&config.led_indicators, "default", false);
+    assert_led_events(leds.take_changes(), vec![led(LedCode::LED_SCROLLL, false)]);
+}
+
+#[test]
+fn test_led_indicator_for_mark() {
+    let config = parse_config_for_test(indoc! {"
+        led_indicators:
+          - led: LED_COMPOSE
+            mode: [nav, select]
+          - led: LED_COMPOSE
+            mark: true
+        "});
+    let mut leds = Leds::new(&config, "xremap");
+
+    leds.update_indicators(&config.led_indicators, "default", true);
+    assert_led_events(leds.take_changes(), vec![led(LedCode::LED_COMPOSE, true)]);
+
+    leds.update_indicators(&config.led_indicators, "select", false);
+    assert_led_events(leds.take_changes(), vec![]);
+    assert_led_events(leds.events(), vec![led(LedCode::LED_COMPOSE, true)]);
+
+    leds.update_indicators(&config.led_indicators, "default", false);
+    assert_led_events(leds.take_changes(), vec![led(LedCode::LED_COMPOSE, false)]);
+}
@@ -0,0 +1,84 @@
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::tests::{get_input_device_info, EventHandlerForTest};
+use evdev::{EventType, InputEvent, KeyCode as Key, LedCode};
+use indoc::indoc;
+use std::time::Duration;
+
+fn led(led: LedCode, on: bool) -> InputEvent {
+    InputEvent::new(EventType::LED.0, led.0, on as i32)
+}
+
+#[test]
+fn test_keymap_lock_from_synced_leds() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        keymap:
+          - lock: { not: numlock }
+            remap:
+              KEY_KP4: LEFT
+        "});
+    handler.assert(
+        vec![Event::key_press(Key::KEY_KP4)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFT, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFT, KeyValue::Release)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::Delay(Duration::from_nanos(0)),
+        ],
+    );
+    handler.set_lock(LedCode::LED_NUML, true);
+    handler.assert(
+        vec![Event::key_press(Key::KEY_KP4)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_KP4, KeyValue::Press))],
+    );
+}
+
+#[test]
+fn test_keymap_lock_ignores_led_events() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        keymap:
+          - lock: { only: capslock }
+            remap:
+              KEY_J: DOWN
+        "});
+    // The echo of a led_indicators LED, not a lock
+    handler.assert(
+        vec![Event::new(get_input_device_info(), led(LedCode::LED_CAPSL, true))],
+        vec![Action::InputEvent(led(LedCode::LED_CAPSL, true))],
+    );
+    handler.assert(
+        vec![Event::key_press(Key::KEY_J)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Press))],
+    );
+}
+
+#[test]
+fn test_modmap_lock_from_emitted_lock_key() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+          - lock: { only: [capslock] }
+            remap:
+              KEY_J: KEY_DOWN
+        "});
+    handler.assert(
+        vec![Event::key_press(Key::KEY_J), Event::key_release(Key::KEY_J)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Release)),
+        ],
+    );
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_CAPSLOCK),
+            Event::key_release(Key::KEY_CAPSLOCK),
+        ],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_CAPSLOCK, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_CAPSLOCK, KeyValue::Release)),
+        ],
+    );
+    handler.assert(
+        vec![Event::key_press(Key::KEY_J)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_DOWN, KeyValue::Press))],
+    );
+}
@@ -0,0 +1,78 @@
+use crate::device::{extra_device_name, is_mirrored, is_mirrored_button, is_touchpad};
+use crate::tests::parse_config_for_test;
+use evdev::{AbsoluteAxisCode, AttributeSet, EventType, InputEvent, KeyCode as Key, PropType, SynchronizationCode};
+use indoc::indoc;
+
+fn key(key: Key) -> InputEvent {
+    InputEvent::new(EventType::KEY.0, key.code(), 1)
+}
+
+#[test]
+fn test_is_mirrored() {
+    assert!(is_mirrored(&InputEvent::new(EventType::ABSOLUTE.0, AbsoluteAxisCode::ABS_MT_POSITION_X.0, 10)));
+    assert!(is_mirrored(&key(Key::BTN_TOOL_FINGER)));
+    assert!(is_mirrored(&key(Key::BTN_TOOL_QUADTAP)));
+
+    // Buttons go through remapping, and the mirror gets its own SYN_REPORT.
+    assert!(!is_mirrored(&key(Key::BTN_LEFT)));
+    assert!(!is_mirrored(&key(Key::KEY_A)));
+    assert!(!is_mirrored(&InputEvent::new(EventType::SYNCHRONIZATION.0, SynchronizationCode::SYN_REPORT.0, 0)));
+}
+
+#[test]
+fn test_mirror_device_name() {
+    assert_eq!(extra_device_name("xremap", "ELAN Touchpad"), "xremap: ELAN Touchpad");
+
+    // uinput limits the length of names
+    let name = extra_device_name("xremap", &"a".repeat(100));
+    assert_eq!(name.len(), 78);
+    assert!(name.starts_with("xremap: aaa"));
+}
+
+#[test]
+fn test_is_touchpad() {
+    let keys = AttributeSet::from_iter([Key::BTN_LEFT, Key::BTN_TOUCH, Key::BTN_TOOL_FINGER]);
+    let axes = AttributeSet::from_iter([
+        AbsoluteAxisCode::ABS_X,
+        AbsoluteAxisCode::ABS_Y,
+        AbsoluteAxisCode::ABS_MT_POSITION_X,
+        AbsoluteAxisCode::ABS_MT_POSITION_Y,
+    ]);
+    let pointer = AttributeSet::from_iter([PropType::POINTER, PropType::BUTTONPAD]);
+    let direct = AttributeSet::from_iter([PropType::DIRECT]);
+
+    assert!(is_touchpad(Some(&keys), Some(&axes), &pointer));
+
+    // Touchscreen
+    assert!(!is_touchpad(Some(&keys), Some(&axes), &direct));
+
+    // Tablet
+    let pen = AttributeSet::from_iter([Key::BTN_TOUCH, Key::BTN_TOOL_PEN, Key::BTN_STYLUS]);
+    assert!(!is_touchpad(Some(&pen), Some(&axes), &pointer));
+
+    // Absolute pointer of a virtual machine
+    let absolute = AttributeSet::from_iter([AbsoluteAxisCode::ABS_X, AbsoluteAxisCode::ABS_Y]);
+    let buttons = AttributeSet::from_iter([Key::BTN_LEFT, Key::BTN_RIGHT]);
+    assert!(!is_touchpad(Some(&buttons), Some(&absolute), &AttributeSet::new()));
+    assert!(!is_touchpad(None, None, &AttributeSet::new()));
+}
+
+#[test]
+fn test_is_mirrored_button() {
+    let config = parse_config_for_test(indoc! {"
+        modmap:
+          - remap:
+              BTN_MIDDLE: KEY_A
+        keymap:
+          - remap:
+              Ctrl-BTN_RIGHT: C-c
+        "});
+
+    // Clicks stay in the same frame as the fingers
+    assert!(is_mirrored_button(&key(Key::BTN_LEFT), &config));
+
+    // Remapped buttons go to the output device
+    assert!(!is_mirrored_button(&key(Key::BTN_MIDDLE), &config));
+    assert!(!is_mirrored_button(&key(Key::BTN_RIGHT), &config));
+    assert!(!is_mirrored_button(&key(Key::KEY_A), &config));
+}
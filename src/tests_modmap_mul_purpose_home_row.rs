@@ -0,0 +1,230 @@
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::tests::{assert_actions, EventHandlerForTest};
+use evdev::KeyCode as Key;
+use indoc::indoc;
+use std::thread::sleep;
+use std::time::Duration;
+
+static BILATERAL_CONFIG: &str = indoc! {"
+    hands:
+        left: [a, s, d, f]
+        right: [j, k, l]
+    modmap:
+        - remap:
+            S:
+                held: ALT_L
+                alone: S
+                bilateral: true
+    "};
+
+#[test]
+fn test_bilateral_same_hand_is_tap() {
+    assert_actions(
+        BILATERAL_CONFIG,
+        vec![
+            Event::key_press(Key::KEY_S),
+            Event::key_press(Key::KEY_D),
+            Event::key_release(Key::KEY_S),
+            Event::key_release(Key::KEY_D),
+        ],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_D, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_D, KeyValue::Release)),
+        ],
+    );
+}
+
+#[test]
+fn test_bilateral_opposite_hand_is_hold() {
+    assert_actions(
+        BILATERAL_CONFIG,
+        vec![
+            Event::key_press(Key::KEY_S),
+            Event::key_press(Key::KEY_J),
+            Event::key_release(Key::KEY_J),
+            Event::key_release(Key::KEY_S),
+        ],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTALT, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTALT, KeyValue::Release)),
+        ],
+    );
+}
+
+#[test]
+fn test_bilateral_key_without_hand_is_tap() {
+    assert_actions(
+        BILATERAL_CONFIG,
+        vec![
+            Event::key_press(Key::KEY_S),
+            Event::key_press(Key::KEY_SPACE),
+            Event::key_release(Key::KEY_SPACE),
+            Event::key_release(Key::KEY_S),
+        ],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_SPACE, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_SPACE, KeyValue::Release)),
+        ],
+    );
+}
+
+#[test]
+fn test_quick_tap_term_holds_tap() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+            - remap:
+                S:
+                    held: ALT_L
+                    alone: S
+                    quick_tap_term_millis: 1000
+        "});
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_S), Event::key_release(Key::KEY_S)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Release)),
+        ],
+    );
+
+    // Pressed again right away, so the tap-action is held.
+    handler.assert(
+        vec![Event::key_press(Key::KEY_S)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Press))],
+    );
+    handler.assert(
+        vec![Event::key_repeat(Key::KEY_S)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Repeat))],
+    );
+    handler.assert(
+        vec![Event::key_press(Key::KEY_J)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Press))],
+    );
+    handler.assert(
+        vec![Event::key_release(Key::KEY_J), Event::key_release(Key::KEY_S)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Release)),
+        ],
+    );
+}
+
+#[test]
+fn test_quick_tap_term_not_after_hold() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+            - remap:
+                S:
+                    held: ALT_L
+                    alone: S
+                    quick_tap_term_millis: 1000
+        "});
+
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_S),
+            Event::key_press(Key::KEY_J),
+            Event::key_release(Key::KEY_J),
+            Event::key_release(Key::KEY_S),
+        ],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTALT, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTALT, KeyValue::Release)),
+        ],
+    );
+
+    handler.assert(vec![Event::key_press(Key::KEY_S)], vec![]);
+}
+
+#[test]
+fn test_quick_tap_term_expired() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+            - remap:
+                S:
+                    held: ALT_L
+                    alone: S
+                    quick_tap_term_millis: 10
+        "});
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_S), Event::key_release(Key::KEY_S)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Release)),
+        ],
+    );
+
+    sleep(Duration::from_millis(20));
+
+    handler.assert(vec![Event::key_press(Key::KEY_S)], vec![]);
+}
+
+#[test]
+fn test_require_prior_idle() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+            - remap:
+                S:
+                    held: ALT_L
+                    alone: S
+                    require_prior_idle_millis: 1000
+        "});
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_A), Event::key_release(Key::KEY_A)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Release)),
+        ],
+    );
+
+    // Typing, so no hold.
+    handler.assert(
+        vec![Event::key_press(Key::KEY_S), Event::key_press(Key::KEY_J)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Press)),
+        ],
+    );
+    handler.assert(
+        vec![Event::key_release(Key::KEY_S), Event::key_release(Key::KEY_J)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_S, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Release)),
+        ],
+    );
+}
+
+#[test]
+fn test_require_prior_idle_after_idle() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+            - remap:
+                S:
+                    held: ALT_L
+                    alone: S
+                    require_prior_idle_millis: 10
+        "});
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_A), Event::key_release(Key::KEY_A)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Release)),
+        ],
+    );
+
+    sleep(Duration::from_millis(20));
+
+    handler.assert(vec![Event::key_press(Key::KEY_S)], vec![]);
+}
@@ -0,0 +1,249 @@
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::tests::{assert_actions, EventHandlerForTest};
+use evdev::KeyCode as Key;
+use indoc::indoc;
+use std::thread::sleep;
+use std::time::Duration;
+
+static CONFIG: &str = indoc! {"
+    modmap:
+        - remap:
+            SHIFT_L:
+                one_shot: SHIFT_L
+                timeout_millis: 1000
+    "};
+
+#[test]
+fn test_one_shot_modifies_next_key() {
+    let mut handler = EventHandlerForTest::new(CONFIG);
+
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_LEFTSHIFT),
+            Event::key_release(Key::KEY_LEFTSHIFT),
+        ],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Press))],
+    );
+    handler.assert(
+        vec![Event::key_press(Key::KEY_A)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Release)),
+        ],
+    );
+    handler.assert(
+        vec![Event::key_press(Key::KEY_B)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_B, KeyValue::Press))],
+    );
+}
+
+#[test]
+fn test_one_shot_held_is_normal_modifier() {
+    assert_actions(
+        CONFIG,
+        vec![
+            Event::key_press(Key::KEY_LEFTSHIFT),
+            Event::key_press(Key::KEY_A),
+            Event::key_release(Key::KEY_A),
+            Event::key_release(Key::KEY_LEFTSHIFT),
+            Event::key_press(Key::KEY_B),
+        ],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_B, KeyValue::Press)),
+        ],
+    );
+}
+
+#[test]
+fn test_one_shot_not_used_by_modifier() {
+    assert_actions(
+        CONFIG,
+        vec![
+            Event::key_press(Key::KEY_LEFTSHIFT),
+            Event::key_release(Key::KEY_LEFTSHIFT),
+            Event::key_press(Key::KEY_LEFTCTRL),
+            Event::key_press(Key::KEY_A),
+        ],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTCTRL, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Release)),
+        ],
+    );
+}
+
+#[test]
+fn test_one_shot_double_tap_locks() {
+    let mut handler = EventHandlerForTest::new(CONFIG);
+
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_LEFTSHIFT),
+            Event::key_release(Key::KEY_LEFTSHIFT),
+            Event::key_press(Key::KEY_LEFTSHIFT),
+            Event::key_release(Key::KEY_LEFTSHIFT),
+        ],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Press))],
+    );
+    handler.assert(
+        vec![Event::key_press(Key::KEY_A), Event::key_press(Key::KEY_B)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_B, KeyValue::Press)),
+        ],
+    );
+
+    // Unlock
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_LEFTSHIFT),
+            Event::key_release(Key::KEY_LEFTSHIFT),
+        ],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Release))],
+    );
+    handler.assert(
+        vec![Event::key_press(Key::KEY_C)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_C, KeyValue::Press))],
+    );
+}
+
+#[test]
+fn test_one_shot_timeout() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+            - remap:
+                SHIFT_L:
+                    one_shot: SHIFT_L
+                    timeout_millis: 10
+        "});
+
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_LEFTSHIFT),
+            Event::key_release(Key::KEY_LEFTSHIFT),
+        ],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Press))],
+    );
+
+    sleep(Duration::from_millis(20));
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_A)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+        ],
+    );
+}
+
+#[test]
+fn test_one_shot_timeout_on_tick() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+            - remap:
+                SHIFT_L:
+                    one_shot: SHIFT_L
+                    timeout_millis: 10
+                F13:
+                    one_shot: []
+                    mode: nav
+                    timeout_millis: 10
+        keymap:
+            - mode: nav
+              remap:
+                J: DOWN
+        "});
+
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_LEFTSHIFT),
+            Event::key_release(Key::KEY_LEFTSHIFT),
+            Event::key_press(Key::KEY_F13),
+            Event::key_release(Key::KEY_F13),
+        ],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Press))],
+    );
+    handler.assert(vec![Event::Tick], vec![]);
+
+    sleep(Duration::from_millis(20));
+
+    handler.assert(vec![Event::Tick], vec![Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTSHIFT, KeyValue::Release))]);
+    handler.assert(
+        vec![Event::key_press(Key::KEY_J)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Press))],
+    );
+}
+
+#[test]
+fn test_one_shot_virtual_modifier() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        virtual_modifiers:
+            - F13
+        modmap:
+            - remap:
+                CAPSLOCK:
+                    one_shot: F13
+        keymap:
+            - remap:
+                F13-J: DOWN
+        "});
+
+    handler.assert(
+        vec![
+            Event::key_press(Key::KEY_CAPSLOCK),
+            Event::key_release(Key::KEY_CAPSLOCK),
+        ],
+        vec![],
+    );
+    handler.assert(
+        vec![Event::key_press(Key::KEY_J), Event::key_release(Key::KEY_J)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_DOWN, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_DOWN, KeyValue::Release)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Release)),
+        ],
+    );
+    handler.assert(
+        vec![Event::key_press(Key::KEY_J)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Press))],
+    );
+}
+
+#[test]
+fn test_one_shot_mode() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+            - remap:
+                F13:
+                    one_shot: []
+                    mode: nav
+        keymap:
+            - mode: nav
+              remap:
+                J: DOWN
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_F13), Event::key_release(Key::KEY_F13)], vec![]);
+    handler.assert(
+        vec![Event::key_press(Key::KEY_J), Event::key_release(Key::KEY_J)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_DOWN, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_DOWN, KeyValue::Release)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Release)),
+        ],
+    );
+    handler.assert(
+        vec![Event::key_press(Key::KEY_J)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Press))],
+    );
+}
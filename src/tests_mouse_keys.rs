@@ -0,0 +1,171 @@
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue, RelativeEvent};
+use crate::tests::EventHandlerForTest;
+use evdev::{KeyCode as Key, RelativeAxisCode};
+use indoc::indoc;
+use std::thread::sleep;
+use std::time::Duration;
+
+static INTERVAL: Duration = Duration::from_millis(20);
+
+// The total movement in the actions. The high resolution value for scrolling.
+fn moved(actions: Vec<Action>) -> (i32, i32) {
+    actions.iter().fold((0, 0), |(x, y), action| match action {
+        Action::MouseMovementEventCollection(events) => events.iter().fold((x, y), |(x, y), event| {
+            if event.code == RelativeAxisCode::REL_X.0 || event.code == RelativeAxisCode::REL_HWHEEL_HI_RES.0 {
+                (x + event.value, y)
+            } else if event.code == RelativeAxisCode::REL_Y.0 || event.code == RelativeAxisCode::REL_WHEEL_HI_RES.0 {
+                (x, y + event.value)
+            } else if event.code == RelativeAxisCode::REL_WHEEL.0 || event.code == RelativeAxisCode::REL_HWHEEL.0 {
+                (x, y)
+            } else {
+                panic!("Unexpected event {event:?}")
+            }
+        }),
+        _ => panic!("Unexpected action {action:?}"),
+    })
+}
+
+fn get_handler() -> EventHandlerForTest {
+    EventHandlerForTest::new(indoc! {"
+        mouse_keys:
+          initial_speed: 1000
+          max_speed: 1000
+          precision_keys: [Alt_L]
+          precision_speed: 100
+        keymap:
+          - remap:
+              KP6: { mouse_move: { x: 1 } }
+              KP2: { mouse_move: { y: 1 } }
+              M-KP4: { mouse_move: { x: -1 } }
+        "})
+}
+
+#[test]
+fn test_mouse_keys_move_while_held() {
+    let mut handler = get_handler();
+
+    handler.assert(vec![Event::key_press(Key::KEY_KP6)], vec![]);
+
+    sleep(INTERVAL);
+
+    let (x, y) = moved(handler.on_events(vec![Event::Tick]));
+    assert!((20..100).contains(&x), "Moved {x}");
+    assert_eq!(y, 0);
+
+    // Repeat doesn't restart the movement
+    handler.assert(vec![Event::key_repeat(Key::KEY_KP6)], vec![]);
+    handler.assert(vec![Event::key_press(Key::KEY_KP2)], vec![]);
+
+    sleep(INTERVAL);
+
+    let (x, y) = moved(handler.on_events(vec![Event::Tick]));
+    assert!((20..100).contains(&x), "Moved {x}");
+    assert!((20..100).contains(&y), "Moved {y}");
+
+    handler.assert(
+        vec![Event::key_release(Key::KEY_KP6), Event::key_release(Key::KEY_KP2)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_KP6, KeyValue::Release)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_KP2, KeyValue::Release)),
+        ],
+    );
+
+    sleep(INTERVAL);
+
+    handler.assert(vec![Event::Tick], vec![]);
+}
+
+#[test]
+fn test_mouse_keys_precision() {
+    let mut handler = get_handler();
+
+    handler.assert(
+        vec![Event::key_press(Key::KEY_LEFTALT)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTALT, KeyValue::Press))],
+    );
+    handler.assert(vec![Event::key_press(Key::KEY_KP4)], vec![]);
+
+    sleep(INTERVAL);
+
+    let (x, y) = moved(handler.on_events(vec![Event::Tick]));
+    assert!((-10..=-2).contains(&x), "Moved {x}");
+    assert_eq!(y, 0);
+}
+
+#[test]
+fn test_scroll_while_held() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        mouse_keys:
+          scroll_speed: 50
+        keymap:
+          - remap:
+              PAGEDOWN: { scroll: { vertical: -1 } }
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_PAGEDOWN)], vec![]);
+
+    sleep(INTERVAL);
+
+    // 50 notches per second is 120 per notch times 50 times 0.02 seconds.
+    let actions = handler.on_events(vec![Event::Tick]);
+    assert!(format!("{actions:?}").contains("RelativeEvent { code: 8, value: -"));
+    let (x, y) = moved(actions);
+    assert!((-400..=-120).contains(&y), "Scrolled {y}");
+    assert_eq!(x, 0);
+
+    handler.assert(
+        vec![Event::key_release(Key::KEY_PAGEDOWN)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_PAGEDOWN, KeyValue::Release))],
+    );
+
+    sleep(INTERVAL);
+
+    handler.assert(vec![Event::Tick], vec![]);
+}
+
+#[test]
+fn test_drag_scroll() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        mouse_keys:
+          drag_scroll_distance: 10
+        keymap:
+          - remap:
+              BTN_MIDDLE: { drag_scroll: true }
+        "});
+
+    handler.assert(vec![Event::key_press(Key::BTN_MIDDLE)], vec![]);
+    handler.assert(
+        vec![
+            Event::relative(RelativeAxisCode::REL_Y.0, -5),
+            Event::relative(RelativeAxisCode::REL_Y.0, -6),
+            Event::relative(RelativeAxisCode::REL_X.0, 1),
+        ],
+        vec![
+            Action::MouseMovementEventCollection(vec![RelativeEvent::new_with(
+                RelativeAxisCode::REL_WHEEL_HI_RES.0,
+                60,
+            )]),
+            Action::MouseMovementEventCollection(vec![
+                RelativeEvent::new_with(RelativeAxisCode::REL_WHEEL_HI_RES.0, 72),
+                RelativeEvent::new_with(RelativeAxisCode::REL_WHEEL.0, 1),
+            ]),
+            Action::MouseMovementEventCollection(vec![RelativeEvent::new_with(
+                RelativeAxisCode::REL_HWHEEL_HI_RES.0,
+                12,
+            )]),
+        ],
+    );
+
+    handler.assert(
+        vec![Event::key_release(Key::BTN_MIDDLE)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::BTN_MIDDLE, KeyValue::Release))],
+    );
+    handler.assert(
+        vec![Event::relative(RelativeAxisCode::REL_Y.0, -5)],
+        vec![Action::MouseMovementEventCollection(vec![RelativeEvent::new_with(
+            RelativeAxisCode::REL_Y.0,
+            -5,
+        )])],
+    );
+}
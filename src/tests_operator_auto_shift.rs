@@ -0,0 +1,134 @@
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::operator_handler::OperatorHandler;
+use crate::tests::{assert_events, parse_config_for_test, EventHandlerForTest};
+use crate::timeout_manager::TimeoutManager;
+use evdev::KeyCode as Key;
+use indoc::indoc;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+static TIMEOUT: Duration = Duration::from_millis(10);
+
+fn get_handler() -> OperatorHandler {
+    let config = parse_config_for_test(indoc! {"
+        auto_shift:
+          timeout: 10
+          exclude: [b]
+        "});
+
+    OperatorHandler::new(&config.experimental_map, &config.auto_shift, Rc::new(TimeoutManager::new()))
+}
+
+#[test]
+fn test_auto_shift_tapped() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_repeat(Key::KEY_A)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::Tick]), vec![]);
+    assert_events(
+        handler.map_evs(vec![Event::key_release(Key::KEY_A)]),
+        vec![Event::key_press(Key::KEY_A), Event::key_release(Key::KEY_A)],
+    );
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_auto_shift_held() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_1)]), vec![]);
+
+    thread::sleep(TIMEOUT);
+
+    assert_events(
+        handler.map_evs(vec![Event::Tick]),
+        vec![
+            Event::key_press(Key::KEY_LEFTSHIFT),
+            Event::key_press(Key::KEY_1),
+            Event::key_release(Key::KEY_1),
+            Event::key_release(Key::KEY_LEFTSHIFT),
+        ],
+    );
+    assert_events(handler.map_evs(vec![Event::key_repeat(Key::KEY_1)]), vec![]);
+
+    // Other keys aren't affected
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_B)]), vec![Event::key_press(Key::KEY_B)]);
+
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_1)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_B)]), vec![Event::key_release(Key::KEY_B)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_auto_shift_interrupted_by_other_key() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_B)]), vec![]);
+    assert_events(
+        handler.map_evs(vec![Event::key_press(Key::KEY_B)]),
+        vec![
+            Event::key_press(Key::KEY_A),
+            Event::key_release(Key::KEY_B),
+            Event::key_press(Key::KEY_B),
+        ],
+    );
+
+    thread::sleep(TIMEOUT);
+
+    assert_events(handler.map_evs(vec![Event::Tick]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_A)]), vec![Event::key_release(Key::KEY_A)]);
+
+    handler.assert_base_state();
+}
+
+#[test]
+fn test_auto_shift_not_with_modifiers() {
+    let mut handler = get_handler();
+
+    assert_events(
+        handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL), Event::key_press(Key::KEY_A)]),
+        vec![Event::key_press(Key::KEY_LEFTCTRL), Event::key_press(Key::KEY_A)],
+    );
+    assert_events(
+        handler.map_evs(vec![Event::key_release(Key::KEY_A), Event::key_release(Key::KEY_LEFTCTRL)]),
+        vec![Event::key_release(Key::KEY_A), Event::key_release(Key::KEY_LEFTCTRL)],
+    );
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_auto_shift_excluded_key() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_B)]), vec![Event::key_press(Key::KEY_B)]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_SPACE)]), vec![Event::key_press(Key::KEY_SPACE)]);
+
+    handler.assert_base_state();
+}
+
+#[test]
+fn test_auto_shift_application() {
+    let config = indoc! {"
+        auto_shift:
+          application:
+            only: Editor
+        "};
+
+    EventHandlerForTest::new_with_current_application(config, Some("Terminal".into())).assert(
+        vec![Event::key_press(Key::KEY_A)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press))],
+    );
+
+    EventHandlerForTest::new_with_current_application(config, Some("Editor".into()))
+        .assert(vec![Event::key_press(Key::KEY_A)], vec![]);
+}
@@ -1,6 +1,7 @@
-use crate::event::Event;
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue};
 use crate::operator_handler::OperatorHandler;
-use crate::tests::{assert_events, parse_config_for_test};
+use crate::tests::{assert_events, parse_config_for_test, EventHandlerForTest};
 use crate::timeout_manager::TimeoutManager;
 use evdev::KeyCode as Key;
 use indoc::indoc;
@@ -19,7 +20,7 @@ fn get_handler() -> OperatorHandler {
                     timeout: 10
         "});
 
-    OperatorHandler::new(&config.experimental_map, Rc::new(TimeoutManager::new()))
+    OperatorHandler::new(&config.experimental_map, &config.auto_shift, Rc::new(TimeoutManager::new()))
 }
 
 #[test]
@@ -399,3 +400,28 @@ fn test_dbltap_surrounded_at_double_tapped() {
     handler.assert_base_state();
     handler.assert_emitted_modifiers_are_synced();
 }
+
+#[test]
+fn test_dbltap_keymap_action() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        experimental_map:
+            - remap:
+                c_l:
+                    double: [{ launch: [echo] }, a]
+                    timeout: 10
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_LEFTCTRL)], vec![]);
+    handler.assert(vec![Event::key_release(Key::KEY_LEFTCTRL)], vec![]);
+    handler.assert(
+        vec![Event::key_press(Key::KEY_LEFTCTRL)],
+        vec![
+            Action::Command(vec!["echo".to_string()]),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Press)),
+        ],
+    );
+    handler.assert(
+        vec![Event::key_release(Key::KEY_LEFTCTRL)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_A, KeyValue::Release))],
+    );
+}
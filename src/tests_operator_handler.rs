@@ -1,9 +1,11 @@
-use crate::event::Event;
+use crate::device::InputDeviceInfo;
+use crate::event::{Event, KeyEvent, KeyValue};
 use crate::operator_handler::OperatorHandler;
 use crate::tests::{assert_events, parse_config_for_test};
 use crate::timeout_manager::TimeoutManager;
 use evdev::KeyCode as Key;
 use indoc::indoc;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::thread;
 use std::time::Duration;
@@ -33,7 +35,7 @@ fn get_handler() -> OperatorHandler {
                   timeout: 10
         "});
 
-    OperatorHandler::new(&config.experimental_map, Rc::new(TimeoutManager::new()))
+    OperatorHandler::new(&config.experimental_map, &config.auto_shift, Rc::new(TimeoutManager::new()))
 }
 
 #[test]
@@ -360,3 +362,51 @@ fn test_operator_handler_candidate_with_many_actions() {
     handler.assert_base_state();
     handler.assert_emitted_modifiers_are_synced();
 }
+
+#[test]
+fn test_operator_handler_device_condition() {
+    let config = parse_config_for_test(indoc! {"
+        experimental_map:
+            - device:
+                only: [event1]
+              chords:
+                - keys: [A, B]
+                  actions: '1'
+                  timeout: 10
+        "});
+
+    let mut handler =
+        OperatorHandler::new(&config.experimental_map, &config.auto_shift, Rc::new(TimeoutManager::new()));
+
+    // Doesn't apply for event0
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![Event::key_press(Key::KEY_A)]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_B)]), vec![Event::key_press(Key::KEY_B)]);
+
+    handler.assert_base_state();
+
+    let other_device = Rc::new(InputDeviceInfo {
+        name: "Other Device".into(),
+        path: PathBuf::from("/dev/input/event1"),
+        vendor: 0x1234,
+        product: 0x5678,
+        hi_res_wheels: vec![],
+    });
+
+    assert_events(
+        handler.map_evs(vec![Event::KeyEvent(
+            other_device.clone(),
+            KeyEvent::new(Key::KEY_A, KeyValue::Press),
+        )]),
+        vec![],
+    );
+    assert_events(
+        handler.map_evs(vec![Event::KeyEvent(
+            other_device.clone(),
+            KeyEvent::new(Key::KEY_B, KeyValue::Press),
+        )]),
+        vec![Event::KeyEvent(
+            other_device,
+            KeyEvent::new(Key::KEY_1, KeyValue::Press),
+        )],
+    );
+}
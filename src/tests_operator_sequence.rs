@@ -0,0 +1,297 @@
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::operator_handler::OperatorHandler;
+use crate::tests::{assert_events, parse_config_for_test, EventHandlerForTest};
+use crate::timeout_manager::TimeoutManager;
+use evdev::KeyCode as Key;
+use indoc::indoc;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+static TIMEOUT: Duration = Duration::from_millis(10);
+
+fn get_handler() -> OperatorHandler {
+    let config = parse_config_for_test(indoc! {"
+        experimental_map:
+            - sequences:
+                - keys: [J, K]
+                  actions: esc
+                  timeout: 10
+                - keys: [A, B, C]
+                  actions: '1'
+                  timeout: 10
+        "});
+
+    OperatorHandler::new(&config.experimental_map, &config.auto_shift, Rc::new(TimeoutManager::new()))
+}
+
+#[test]
+fn test_sequence_key_not_matching() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_K)]), vec![Event::key_press(Key::KEY_K)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_K)]), vec![Event::key_release(Key::KEY_K)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_tapped_in_order() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_K)]), vec![Event::key_press(Key::KEY_ESC)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_K)]), vec![Event::key_release(Key::KEY_ESC)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_rolled_first_key_released_first() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_K)]), vec![Event::key_press(Key::KEY_ESC)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_K)]), vec![Event::key_release(Key::KEY_ESC)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_rolled_last_key_released_first() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_K)]), vec![Event::key_press(Key::KEY_ESC)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_K)]), vec![Event::key_release(Key::KEY_ESC)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_wrong_order() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_K)]), vec![Event::key_press(Key::KEY_K)]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_K)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![]);
+
+    thread::sleep(TIMEOUT);
+
+    assert_events(
+        handler.map_evs(vec![Event::Tick]),
+        vec![
+            Event::key_press(Key::KEY_J),
+            Event::key_release(Key::KEY_K),
+            Event::key_release(Key::KEY_J),
+        ],
+    );
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_canceled_by_other_key() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_J)]), vec![]);
+    assert_events(
+        handler.map_evs(vec![Event::key_press(Key::KEY_L)]),
+        vec![Event::key_press(Key::KEY_J), Event::key_press(Key::KEY_L)],
+    );
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![Event::key_release(Key::KEY_J)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_L)]), vec![Event::key_release(Key::KEY_L)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_canceled_by_same_key() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![]);
+    // The second press starts matching again.
+    assert_events(
+        handler.map_evs(vec![Event::key_press(Key::KEY_J)]),
+        vec![Event::key_press(Key::KEY_J), Event::key_release(Key::KEY_J)],
+    );
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_K)]), vec![Event::key_press(Key::KEY_ESC)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_K)]), vec![Event::key_release(Key::KEY_ESC)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_canceled_by_timeout() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_J)]), vec![]);
+
+    assert_events(handler.map_evs(vec![Event::Tick]), vec![]);
+
+    thread::sleep(TIMEOUT);
+
+    assert_events(handler.map_evs(vec![Event::Tick]), vec![Event::key_press(Key::KEY_J)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![Event::key_release(Key::KEY_J)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_canceled_by_timeout_after_release() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![]);
+
+    thread::sleep(TIMEOUT);
+
+    assert_events(
+        handler.map_evs(vec![Event::Tick]),
+        vec![Event::key_press(Key::KEY_J), Event::key_release(Key::KEY_J)],
+    );
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_buffers_unrelated_release() {
+    let mut handler = get_handler();
+
+    assert_events(
+        handler.map_evs(vec![Event::key_press(Key::KEY_LEFTSHIFT)]),
+        vec![Event::key_press(Key::KEY_LEFTSHIFT)],
+    );
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_LEFTSHIFT)]), vec![]);
+    assert_events(
+        handler.map_evs(vec![Event::key_press(Key::KEY_K)]),
+        vec![Event::key_press(Key::KEY_ESC), Event::key_release(Key::KEY_LEFTSHIFT)],
+    );
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_K)]), vec![Event::key_release(Key::KEY_ESC)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_repeat() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_repeat(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_K)]), vec![Event::key_press(Key::KEY_ESC)]);
+    assert_events(handler.map_evs(vec![Event::key_repeat(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_repeat(Key::KEY_K)]), vec![Event::key_repeat(Key::KEY_ESC)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_K)]), vec![Event::key_release(Key::KEY_ESC)]);
+    assert_events(handler.map_evs(vec![Event::key_repeat(Key::KEY_J)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_J)]), vec![]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_of_three_keys() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_A)]), vec![]);
+
+    thread::sleep(TIMEOUT / 2);
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_B)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_B)]), vec![]);
+
+    // The timeout is counted from the previous key.
+    thread::sleep(TIMEOUT / 2);
+
+    assert_events(handler.map_evs(vec![Event::Tick]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_C)]), vec![Event::key_press(Key::KEY_1)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_C)]), vec![Event::key_release(Key::KEY_1)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_of_three_keys_canceled_at_second_key() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_A)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_B)]), vec![]);
+
+    thread::sleep(TIMEOUT);
+
+    assert_events(
+        handler.map_evs(vec![Event::Tick]),
+        vec![
+            Event::key_press(Key::KEY_A),
+            Event::key_release(Key::KEY_A),
+            Event::key_press(Key::KEY_B),
+        ],
+    );
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_B)]), vec![Event::key_release(Key::KEY_B)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_sequence_only_in_mode() {
+    let config = indoc! {"
+        default_mode: insert
+        experimental_map:
+            - mode: insert
+              sequences:
+                - keys: [J, K]
+                  actions: esc
+        keymap:
+            - remap:
+                esc: [esc, { set_mode: normal }]
+    "};
+    let mut handler = EventHandlerForTest::new(config);
+
+    handler.assert(vec![Event::key_press(Key::KEY_J), Event::key_release(Key::KEY_J)], vec![]);
+    handler.assert(
+        vec![Event::key_press(Key::KEY_K)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_ESC, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_ESC, KeyValue::Release)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::Delay(Duration::from_nanos(0)),
+        ],
+    );
+    handler.assert(
+        vec![Event::key_release(Key::KEY_K)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_ESC, KeyValue::Release))],
+    );
+
+    // Not matched in other modes, so nothing is delayed.
+    handler.assert(
+        vec![Event::key_press(Key::KEY_J)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Press))],
+    );
+    handler.assert(
+        vec![Event::key_release(Key::KEY_J)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_J, KeyValue::Release))],
+    );
+}
@@ -1,6 +1,7 @@
-use crate::event::Event;
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue};
 use crate::operator_handler::OperatorHandler;
-use crate::tests::{assert_events, parse_config_for_test};
+use crate::tests::{assert_events, parse_config_for_test, EventHandlerForTest};
 use crate::timeout_manager::TimeoutManager;
 use evdev::KeyCode as Key;
 use indoc::indoc;
@@ -22,7 +23,7 @@ fn get_handler() -> OperatorHandler {
                   timeout: 10
         "});
 
-    OperatorHandler::new(&config.experimental_map, Rc::new(TimeoutManager::new()))
+    OperatorHandler::new(&config.experimental_map, &config.auto_shift, Rc::new(TimeoutManager::new()))
 }
 
 #[test]
@@ -395,3 +396,34 @@ fn symkey_test_3_simkeys() {
     handler.assert_base_state();
     handler.assert_emitted_modifiers_are_synced();
 }
+
+#[test]
+fn symkey_test_keymap_actions() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        experimental_map:
+            - chords:
+                - keys: [A, B]
+                  actions: [C-x, { set_mode: other }]
+        keymap:
+            - mode: other
+              remap:
+                  k: { launch: [echo] }
+        "});
+
+    handler.assert(vec![Event::key_press(Key::KEY_A)], vec![]);
+    handler.assert(
+        vec![Event::key_press(Key::KEY_B)],
+        vec![
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTCTRL, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_X, KeyValue::Press)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_X, KeyValue::Release)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::Delay(Duration::from_nanos(0)),
+            Action::KeyEvent(KeyEvent::new(Key::KEY_LEFTCTRL, KeyValue::Release)),
+        ],
+    );
+    // Nothing to release
+    handler.assert(vec![Event::key_release(Key::KEY_A), Event::key_release(Key::KEY_B)], vec![]);
+
+    handler.assert(vec![Event::key_press(Key::KEY_K)], vec![Action::Command(vec!["echo".to_string()])]);
+}
@@ -0,0 +1,226 @@
+use crate::event::Event;
+use crate::operator_handler::OperatorHandler;
+use crate::tests::{assert_events, parse_config_for_test};
+use crate::timeout_manager::TimeoutManager;
+use evdev::KeyCode as Key;
+use indoc::indoc;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+static TIMEOUT: Duration = Duration::from_millis(10);
+
+fn get_handler() -> OperatorHandler {
+    let config = parse_config_for_test(indoc! {"
+        experimental_map:
+            - remap:
+                c_l:
+                    taps: ['1', '2', '3']
+                    hold: [shift_l, null]
+                    timeout: 10
+        "});
+
+    OperatorHandler::new(&config.experimental_map, &config.auto_shift, Rc::new(TimeoutManager::new()))
+}
+
+#[test]
+fn test_tapdance_key_not_matching() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![Event::key_press(Key::KEY_A)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_A)]), vec![Event::key_release(Key::KEY_A)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_tapdance_single_tap_after_timeout() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]), vec![]);
+
+    assert_events(handler.map_evs(vec![Event::Tick]), vec![]);
+
+    thread::sleep(TIMEOUT);
+
+    assert_events(
+        handler.map_evs(vec![Event::Tick]),
+        vec![Event::key_press(Key::KEY_1), Event::key_release(Key::KEY_1)],
+    );
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_tapdance_double_tap_after_timeout() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]), vec![]);
+
+    thread::sleep(TIMEOUT);
+
+    assert_events(
+        handler.map_evs(vec![Event::Tick]),
+        vec![Event::key_press(Key::KEY_2), Event::key_release(Key::KEY_2)],
+    );
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_tapdance_tap_decided_by_other_key() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]), vec![]);
+
+    assert_events(
+        handler.map_evs(vec![Event::key_press(Key::KEY_A)]),
+        vec![
+            Event::key_press(Key::KEY_1),
+            Event::key_release(Key::KEY_1),
+            Event::key_press(Key::KEY_A),
+        ],
+    );
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_A)]), vec![Event::key_release(Key::KEY_A)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_tapdance_tap_with_rolled_key() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![]);
+
+    assert_events(
+        handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]),
+        vec![
+            Event::key_press(Key::KEY_1),
+            Event::key_release(Key::KEY_1),
+            Event::key_press(Key::KEY_A),
+        ],
+    );
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_A)]), vec![Event::key_release(Key::KEY_A)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_tapdance_hold_first_press() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_repeat(Key::KEY_LEFTCTRL)]), vec![]);
+
+    thread::sleep(TIMEOUT);
+
+    assert_events(handler.map_evs(vec![Event::Tick]), vec![Event::key_press(Key::KEY_LEFTSHIFT)]);
+    assert_events(
+        handler.map_evs(vec![Event::key_repeat(Key::KEY_LEFTCTRL)]),
+        vec![Event::key_repeat(Key::KEY_LEFTSHIFT)],
+    );
+
+    // Unrelated keys pass through.
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![Event::key_press(Key::KEY_A)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_A)]), vec![Event::key_release(Key::KEY_A)]);
+
+    assert_events(
+        handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]),
+        vec![Event::key_release(Key::KEY_LEFTSHIFT)],
+    );
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_tapdance_hold_flushes_buffered_events() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![]);
+
+    thread::sleep(TIMEOUT);
+
+    assert_events(
+        handler.map_evs(vec![Event::Tick]),
+        vec![Event::key_press(Key::KEY_LEFTSHIFT), Event::key_press(Key::KEY_A)],
+    );
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_A)]), vec![Event::key_release(Key::KEY_A)]);
+    assert_events(
+        handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]),
+        vec![Event::key_release(Key::KEY_LEFTSHIFT)],
+    );
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_tapdance_hold_with_null_action() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+
+    thread::sleep(TIMEOUT);
+
+    // The hold action for two taps is null, so nothing is emitted.
+    assert_events(handler.map_evs(vec![Event::Tick]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]), vec![]);
+
+    handler.assert_base_state();
+}
+
+#[test]
+fn test_tapdance_last_press_held_right_away() {
+    let mut handler = get_handler();
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]), vec![]);
+
+    // There is no hold action for three taps, so the tap action is held at once.
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_LEFTCTRL)]), vec![Event::key_press(Key::KEY_3)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_LEFTCTRL)]), vec![Event::key_release(Key::KEY_3)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
+
+#[test]
+fn test_tapdance_without_hold() {
+    let config = parse_config_for_test(indoc! {"
+        experimental_map:
+            - remap:
+                a:
+                    taps: [b, c]
+                    timeout: 10
+        "});
+
+    let mut handler =
+        OperatorHandler::new(&config.experimental_map, &config.auto_shift, Rc::new(TimeoutManager::new()));
+
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_A)]), vec![]);
+
+    // Second press holds the last tap action right away.
+    assert_events(handler.map_evs(vec![Event::key_press(Key::KEY_A)]), vec![Event::key_press(Key::KEY_C)]);
+    assert_events(handler.map_evs(vec![Event::key_release(Key::KEY_A)]), vec![Event::key_release(Key::KEY_C)]);
+
+    handler.assert_base_state();
+    handler.assert_emitted_modifiers_are_synced();
+}
@@ -0,0 +1,111 @@
+use crate::action_dispatcher::{route, Output, PressedKeys};
+use crate::device::Capabilities;
+use evdev::{AttributeSet, EventType, InputEvent, KeyCode as Key, LedCode, MiscCode, RelativeAxisCode};
+use std::path::{Path, PathBuf};
+
+fn key(key: Key) -> InputEvent {
+    InputEvent::new(EventType::KEY.0, key.code(), 1)
+}
+
+fn release(key: Key) -> InputEvent {
+    InputEvent::new(EventType::KEY.0, key.code(), 0)
+}
+
+fn relative(axis: RelativeAxisCode) -> InputEvent {
+    InputEvent::new(EventType::RELATIVE.0, axis.0, 1)
+}
+
+fn keyboard() -> Capabilities {
+    Capabilities {
+        keys: AttributeSet::from_iter([Key::KEY_A, Key::KEY_LEFTCTRL]),
+        relative_axes: AttributeSet::new(),
+        msc: AttributeSet::from_iter([MiscCode::MSC_SCAN]),
+    }
+}
+
+fn mouse() -> Capabilities {
+    Capabilities {
+        keys: AttributeSet::from_iter([Key::BTN_LEFT, Key::BTN_RIGHT]),
+        relative_axes: AttributeSet::from_iter([RelativeAxisCode::REL_X, RelativeAxisCode::REL_Y]),
+        msc: AttributeSet::new(),
+    }
+}
+
+#[test]
+fn test_route_without_source() {
+    assert_eq!(route(&key(Key::KEY_A), None, false), Output::Main);
+    assert_eq!(route(&key(Key::BTN_LEFT), None, false), Output::Main);
+    assert_eq!(route(&relative(RelativeAxisCode::REL_X), None, false), Output::Main);
+}
+
+#[test]
+fn test_route_split_output() {
+    assert_eq!(route(&key(Key::KEY_A), None, true), Output::Main);
+    assert_eq!(route(&key(Key::BTN_LEFT), None, true), Output::Mouse);
+    assert_eq!(route(&relative(RelativeAxisCode::REL_WHEEL), None, true), Output::Mouse);
+
+    // The keyboard only has keyboard keys
+    assert_eq!(route(&key(Key::BTN_0), None, true), Output::Mouse);
+    assert_eq!(route(&key(Key::BTN_TRIGGER), None, true), Output::Mouse);
+    assert_eq!(route(&key(Key::BTN_TOOL_FINGER), None, true), Output::Mouse);
+    assert_eq!(route(&key(Key::BTN_TRIGGER_HAPPY1), None, true), Output::Mouse);
+    assert_eq!(route(&key(Key::KEY_OK), None, true), Output::Main);
+    assert_eq!(route(&key(Key::KEY_BRIGHTNESS_MIN), None, true), Output::Main);
+}
+
+#[test]
+fn test_route_output_per_device() {
+    let keyboard = keyboard();
+    assert_eq!(route(&key(Key::KEY_A), Some(&keyboard), false), Output::Source);
+    assert_eq!(
+        route(&InputEvent::new(EventType::MISC.0, MiscCode::MSC_SCAN.0, 30), Some(&keyboard), false),
+        Output::Source
+    );
+
+    // Remapped to events the input device doesn't have
+    assert_eq!(route(&key(Key::KEY_B), Some(&keyboard), false), Output::Main);
+    assert_eq!(route(&key(Key::BTN_LEFT), Some(&keyboard), true), Output::Mouse);
+    assert_eq!(route(&relative(RelativeAxisCode::REL_X), Some(&keyboard), false), Output::Main);
+    assert_eq!(
+        route(&InputEvent::new(EventType::LED.0, LedCode::LED_CAPSL.0, 1), Some(&keyboard), false),
+        Output::Main
+    );
+
+    let mouse = mouse();
+    assert_eq!(route(&key(Key::BTN_LEFT), Some(&mouse), true), Output::Source);
+    assert_eq!(route(&relative(RelativeAxisCode::REL_Y), Some(&mouse), true), Output::Source);
+    assert_eq!(route(&key(Key::KEY_C), Some(&mouse), true), Output::Main);
+}
+
+#[test]
+fn test_same_key_pressed_for_two_devices() {
+    let a = PathBuf::from("/dev/input/event1");
+    let b = PathBuf::from("/dev/input/event2");
+    let mut pressed_keys = PressedKeys::default();
+    assert_eq!(pressed_keys.track(&key(Key::KEY_LEFTSHIFT), Some(a.clone())), Some(a.clone()));
+    assert_eq!(pressed_keys.track(&key(Key::KEY_LEFTSHIFT), Some(b.clone())), Some(b.clone()));
+
+    // Each release goes to the output its press went to
+    assert_eq!(pressed_keys.track(&release(Key::KEY_LEFTSHIFT), Some(a.clone())), Some(a.clone()));
+    assert_eq!(pressed_keys.pressed_for(None), vec![(Some(b.clone()), Key::KEY_LEFTSHIFT.code())]);
+    assert_eq!(pressed_keys.track(&release(Key::KEY_LEFTSHIFT), Some(b.clone())), Some(b.clone()));
+    assert_eq!(pressed_keys.pressed_for(None), vec![]);
+}
+
+#[test]
+fn test_release_pressed_keys_of_removed_device() {
+    let a = PathBuf::from("/dev/input/event1");
+    let b = PathBuf::from("/dev/input/event2");
+    let mut pressed_keys = PressedKeys::default();
+    pressed_keys.track(&key(Key::KEY_LEFTSHIFT), Some(a.clone()));
+    pressed_keys.track(&key(Key::KEY_LEFTSHIFT), Some(b.clone()));
+    assert_eq!(
+        pressed_keys.pressed_for(Some(Path::new("/dev/input/event1"))),
+        vec![(Some(a.clone()), Key::KEY_LEFTSHIFT.code())]
+    );
+
+    // A release without a source, e.g. from a timeout, still goes to the output of the press
+    let released = pressed_keys.track(&release(Key::KEY_LEFTSHIFT), None);
+    assert!(released == Some(a) || released == Some(b));
+    assert_eq!(pressed_keys.pressed_for(None).len(), 1);
+}
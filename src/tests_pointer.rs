@@ -0,0 +1,159 @@
+use crate::action::Action;
+use crate::device::InputDeviceInfo;
+use crate::event::{Event, RelativeEvent};
+use crate::tests::{assert_actions, get_input_device_info, EventHandlerForTest};
+use evdev::{EventType, InputEvent, RelativeAxisCode};
+use indoc::indoc;
+use std::rc::Rc;
+
+fn movement(events: &[(RelativeAxisCode, i32)]) -> Action {
+    Action::MouseMovementEventCollection(
+        events
+            .iter()
+            .map(|(code, value)| RelativeEvent::new_with(code.0, *value))
+            .collect(),
+    )
+}
+
+fn wheel(code: RelativeAxisCode, value: i32) -> Action {
+    Action::RelativeEvent(RelativeEvent::new_with(code.0, value))
+}
+
+#[test]
+fn test_pointer_sensitivity_keeps_remainder() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        pointer:
+          - device:
+              only: Some Device
+            sensitivity: 0.5
+        "});
+
+    handler.assert(
+        vec![
+            Event::relative(RelativeAxisCode::REL_X.0, 3),
+            Event::relative(RelativeAxisCode::REL_Y.0, -1),
+        ],
+        vec![movement(&[(RelativeAxisCode::REL_X, 1)])],
+    );
+    handler.assert(
+        vec![
+            Event::relative(RelativeAxisCode::REL_X.0, 1),
+            Event::relative(RelativeAxisCode::REL_Y.0, -1),
+        ],
+        vec![movement(&[(RelativeAxisCode::REL_X, 1), (RelativeAxisCode::REL_Y, -1)])],
+    );
+}
+
+#[test]
+fn test_pointer_other_device() {
+    assert_actions(
+        indoc! {"
+        pointer:
+          - device:
+              only: Other Device
+            sensitivity: 2
+        "},
+        vec![Event::relative(RelativeAxisCode::REL_X.0, 3)],
+        vec![movement(&[(RelativeAxisCode::REL_X, 3)])],
+    );
+}
+
+#[test]
+fn test_pointer_acceleration() {
+    assert_actions(
+        indoc! {"
+        pointer:
+          - acceleration: 2
+        "},
+        vec![
+            Event::relative(RelativeAxisCode::REL_X.0, -3),
+            Event::relative(RelativeAxisCode::REL_Y.0, 1),
+        ],
+        vec![movement(&[(RelativeAxisCode::REL_X, -9), (RelativeAxisCode::REL_Y, 1)])],
+    );
+}
+
+#[test]
+fn test_pointer_swap_and_invert() {
+    assert_actions(
+        indoc! {"
+        pointer:
+          - swap_xy: true
+            invert: [x, wheel]
+        "},
+        vec![
+            Event::relative(RelativeAxisCode::REL_X.0, 2),
+            Event::relative(RelativeAxisCode::REL_Y.0, 5),
+            Event::relative(RelativeAxisCode::REL_WHEEL.0, 1),
+            Event::relative(RelativeAxisCode::REL_HWHEEL.0, 1),
+        ],
+        vec![
+            wheel(RelativeAxisCode::REL_WHEEL, -1),
+            wheel(RelativeAxisCode::REL_HWHEEL, 1),
+            movement(&[(RelativeAxisCode::REL_Y, 2), (RelativeAxisCode::REL_X, -5)]),
+        ],
+    );
+}
+
+#[test]
+fn test_pointer_wheel_low_res() {
+    assert_actions(
+        indoc! {"
+        pointer:
+          - wheel: low_res
+        "},
+        vec![
+            Event::relative(RelativeAxisCode::REL_WHEEL_HI_RES.0, -120),
+            Event::relative(RelativeAxisCode::REL_WHEEL.0, -1),
+        ],
+        vec![
+            wheel(RelativeAxisCode::REL_WHEEL_HI_RES, -120),
+            wheel(RelativeAxisCode::REL_WHEEL, -1),
+        ],
+    );
+}
+
+#[test]
+fn test_pointer_wheel_hi_res() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        pointer:
+          - wheel: hi_res
+        "});
+
+    handler.assert(
+        vec![Event::relative(RelativeAxisCode::REL_HWHEEL_HI_RES.0, 90)],
+        vec![wheel(RelativeAxisCode::REL_HWHEEL_HI_RES, 90)],
+    );
+    handler.assert(
+        vec![
+            Event::relative(RelativeAxisCode::REL_HWHEEL_HI_RES.0, 60),
+            Event::relative(RelativeAxisCode::REL_HWHEEL.0, 1),
+        ],
+        vec![
+            wheel(RelativeAxisCode::REL_HWHEEL_HI_RES, 60),
+            wheel(RelativeAxisCode::REL_HWHEEL, 1),
+        ],
+    );
+}
+
+#[test]
+fn test_pointer_wheel_hi_res_without_hi_res_wheel() {
+    let device = Rc::new(InputDeviceInfo {
+        hi_res_wheels: vec![],
+        ..(*get_input_device_info()).clone()
+    });
+    assert_actions(
+        indoc! {"
+        pointer:
+          - wheel: hi_res
+        "},
+        vec![Event::new(
+            device,
+            InputEvent::new(EventType::RELATIVE.0, RelativeAxisCode::REL_WHEEL.0, -1),
+        )],
+        vec![
+            wheel(RelativeAxisCode::REL_WHEEL_HI_RES, -120),
+            wheel(RelativeAxisCode::REL_WHEEL, -1),
+        ],
+    );
+}
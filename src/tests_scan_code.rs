@@ -0,0 +1,49 @@
+use crate::action::Action;
+use crate::event::{Event, KeyEvent, KeyValue};
+use crate::tests::{get_input_device_info, EventHandlerForTest};
+use evdev::{EventType, InputEvent, KeyCode as Key, MiscCode};
+use indoc::indoc;
+
+fn scan(value: i32) -> InputEvent {
+    InputEvent::new(EventType::MISC.0, MiscCode::MSC_SCAN.0, value)
+}
+
+#[test]
+fn test_scan_code_of_key_that_is_not_remapped() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+          - remap:
+              KEY_A: KEY_B
+        "});
+    handler.assert(
+        vec![
+            Event::new(get_input_device_info(), scan(0x70006)),
+            Event::key_press(Key::KEY_C),
+        ],
+        vec![Action::ScannedKeyEvent(
+            KeyEvent::new(Key::KEY_C, KeyValue::Press),
+            0x70006,
+        )],
+    );
+}
+
+#[test]
+fn test_scan_code_of_remapped_key_is_dropped() {
+    let mut handler = EventHandlerForTest::new(indoc! {"
+        modmap:
+          - remap:
+              KEY_A: KEY_B
+        "});
+    handler.assert(
+        vec![
+            Event::new(get_input_device_info(), scan(0x70004)),
+            Event::key_press(Key::KEY_A),
+        ],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_B, KeyValue::Press))],
+    );
+    // The scan code isn't kept for the next key.
+    handler.assert(
+        vec![Event::key_press(Key::KEY_B)],
+        vec![Action::KeyEvent(KeyEvent::new(Key::KEY_B, KeyValue::Press))],
+    );
+}
@@ -20,7 +20,7 @@ pub fn test_no_input_device_match() {
 
     assert_err(
         "Failed to prepare input devices: No device was selected!",
-        select_input_devices(&device_filter, &vec![], false, false, "own_device"),
+        select_input_devices(&device_filter, &vec![], false, false, false, "own_device"),
     );
 }
 
@@ -32,7 +32,7 @@ pub fn test_device_filter_overwrites_keyboard_and_mouse_check() -> Result<()> {
     let _ = wait_for_device(&name)?;
 
     // Selects the device, because filter overwrites the automatic selection rules.
-    let names: Vec<String> = select_input_devices(&[name.clone()], &vec![], false, false, "own_device")?
+    let names: Vec<String> = select_input_devices(&[name.clone()], &vec![], false, false, false, "own_device")?
         .iter()
         .map(|(_, device)| device.device_name().to_string())
         .collect();
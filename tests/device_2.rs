@@ -17,7 +17,7 @@ pub fn test_device_without_keys_is_not_selected_automatically() -> Result<()> {
     let _ = wait_for_device(&name)?;
 
     // Automatically select devices
-    match select_input_devices(&[], &vec![], false, false, "own_device") {
+    match select_input_devices(&[], &vec![], false, false, false, "own_device") {
         Ok(devices) => assert_eq!(
             0,
             devices
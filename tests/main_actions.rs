@@ -30,8 +30,7 @@ pub fn e2e_events_around_exit_action() -> anyhow::Result<()> {
         key_release(KeyCode::KEY_K),
     ])?;
 
-    // Note: KEY_A is held down at exit. But a release event is inserted
-    //       by the kernel. This means clean up is very easy.
+    // Note: KEY_A is held down at exit, so xremap releases it.
     assert_events(
         ctrl.fetch_until_end()?,
         indoc! {"
@@ -186,7 +185,7 @@ pub fn e2e_mode_is_preserved_when_reloading_config() -> anyhow::Result<()> {
 }
 
 #[test]
-pub fn e2e_reload_config_inconsistency_example() -> anyhow::Result<()> {
+pub fn e2e_reload_config_releases_pressed_keys() -> anyhow::Result<()> {
     let mut ctrl = XremapController::builder()
         .config(indoc! {"
               keymap:
@@ -201,13 +200,13 @@ pub fn e2e_reload_config_inconsistency_example() -> anyhow::Result<()> {
 
     ctrl.emit_events(&vec![key_press(KeyCode::KEY_F12), key_release(KeyCode::KEY_A)])?;
 
-    // KEY_A is not released, because reload_config by-design squash all actions
-    // that comes after it. It could be improved, but the problem of reloading
-    // config consistently can't really be fixed with reasonable effort.
+    // reload_config by-design squash all actions that comes after it,
+    // so KEY_A is released because it's pressed when reloading.
     assert_events(
         ctrl.fetch()?,
         indoc! {"
             a:1
+            a:0
         "},
     );
 